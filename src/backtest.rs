@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Date, Game};
+use crate::models::{load_json, save_json};
+use crate::prediction::{Prediction, Predictor, RUN_LINE};
+
+/// The directory backtest predictions are saved in.
+pub const BACKTESTS_DIR: &str = "data/backtests";
+
+/// How often the model is refit as the backtest moves forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    Daily,
+    Weekly,
+}
+
+impl Step {
+    fn days(&self) -> i32 {
+        match self {
+            Step::Daily => 1,
+            Step::Weekly => 7,
+        }
+    }
+}
+
+/// One prediction made during a backtest, with the game's result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestRecord {
+    pub date: Date,
+    pub game_pk: Option<u32>,
+    pub home_team_id: u8,
+    pub away_team_id: u8,
+    /// The model's version and settings, and the version of this crate.
+    pub model_version: String,
+    /// The number of games the model was trained on, all of them before the date of the last refit.
+    pub training_games: usize,
+    /// The date the model was last refit, which is at most a step before `date`.
+    pub trained_on: Date,
+    pub prediction: Prediction,
+    pub home_team_score: u8,
+    pub away_team_score: u8,
+}
+
+/// The predictions of one backtest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backtest {
+    pub from: Date,
+    pub to: Date,
+    pub step: Step,
+    pub records: Vec<BacktestRecord>,
+}
+
+impl Backtest {
+    pub fn load(path: &str) -> Result<Self, String> {
+        load_json(path)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        save_json(self, path)
+    }
+
+    /// Save every prediction's probabilities of the over at each total runs and first five innings line (and of the
+    /// under, which differ from one minus the over on whole-number lines by the push) and of each side of the run
+    /// line with either team giving the runs, as a CSV file. Cells the model doesn't predict are empty.
+    pub fn save_markets(&self, path: &str, total_lines: &[f64], first_five_lines: &[f64]) -> Result<(), String> {
+        let mut header = vec!["date", "game_pk", "home_team_id", "away_team_id", "model_version", "expected_home_runs", "expected_away_runs", "home_win"]
+            .into_iter()
+            .map(|column| column.to_string())
+            .collect::<Vec<String>>();
+        for line in total_lines {
+            header.push(format!("over_{}", line));
+            header.push(format!("under_{}", line));
+        }
+        header.push(format!("home_-{}", RUN_LINE));
+        header.push(format!("away_+{}", RUN_LINE));
+        header.push(format!("home_+{}", RUN_LINE));
+        header.push(format!("away_-{}", RUN_LINE));
+        for line in first_five_lines {
+            header.push(format!("first_five_over_{}", line));
+            header.push(format!("first_five_under_{}", line));
+        }
+        header.push("home_team_score".to_string());
+        header.push("away_team_score".to_string());
+
+        let cell = |value: Option<f64>| value.map_or(String::new(), |value| value.to_string());
+        let mut csv = header.join(",") + "\n";
+        for record in &self.records {
+            let prediction = &record.prediction;
+            let mut row = vec![
+                record.date.to_string(),
+                record.game_pk.map_or(String::new(), |game_pk| game_pk.to_string()),
+                record.home_team_id.to_string(),
+                record.away_team_id.to_string(),
+                format!("\"{}\"", record.model_version),
+                cell(prediction.expected_home_runs),
+                cell(prediction.expected_away_runs),
+                cell(prediction.home_win_probability),
+            ];
+            for line in total_lines {
+                let over_under = prediction.total_runs(*line);
+                row.push(cell(over_under.map(|over_under| over_under.over)));
+                row.push(cell(over_under.map(|over_under| over_under.under)));
+            }
+            // the home team giving the runs, then the away team
+            for spread in [RUN_LINE, -RUN_LINE] {
+                let run_line = prediction.run_line(spread);
+                row.push(cell(run_line.map(|run_line| run_line.home)));
+                row.push(cell(run_line.map(|run_line| run_line.away)));
+            }
+            for line in first_five_lines {
+                let over_under = prediction.first_five_total_runs(*line);
+                row.push(cell(over_under.map(|over_under| over_under.over)));
+                row.push(cell(over_under.map(|over_under| over_under.under)));
+            }
+            row.push(record.home_team_score.to_string());
+            row.push(record.away_team_score.to_string());
+
+            csv += &(row.join(",") + "\n");
+        }
+
+        if let Some(dir) = std::path::Path::new(path).parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create directories: {}", e))?;
+        }
+        std::fs::write(path, csv)
+            .map_err(|e| format!("Failed to write file {}: {}", path, e))
+    }
+}
+
+/// Walk forward through the dates from `from` to `to`, predicting each date's games with the model trained on the
+/// games strictly before it, and refitting it every `step`.
+///
+/// The model is first fit going into `from`, whatever the step, and if a fit fails (e.g. on too few games) the date's
+/// games are skipped and the model is refit on the next date. `games` must be in chronological order.
+pub fn walk_forward(predictor: &mut dyn Predictor, games: &[Game], from: Date, to: Date, step: Step) -> Backtest {
+    let model_version = format!("{} {}", predictor.version(), env!("CARGO_PKG_VERSION"));
+
+    let mut records = Vec::new();
+    let mut trained: Option<(Date, usize)> = None;
+    let mut start = games.iter().take_while(|game| game.setting.date < from).count();
+    while start < games.len() && games[start].setting.date <= to {
+        let date = games[start].setting.date;
+        let end = start + games[start..].iter().take_while(|game| game.setting.date == date).count();
+
+        if trained.is_none_or(|(trained_on, _)| date.days_since(&trained_on) >= step.days()) {
+            let training: Vec<&Game> = games[..start].iter().collect();
+            trained = match predictor.train(&training) {
+                Ok(()) => Some((date, training.len())),
+                Err(e) => {
+                    eprintln!("Skipping {}: {}", date, e);
+                    None
+                },
+            };
+        }
+
+        if let Some((trained_on, training_games)) = trained {
+            for game in &games[start..end] {
+                records.push(BacktestRecord {
+                    date,
+                    game_pk: game.setting.game_pk,
+                    home_team_id: game.home_team.team_id,
+                    away_team_id: game.away_team.team_id,
+                    model_version: model_version.clone(),
+                    training_games,
+                    trained_on,
+                    prediction: predictor.predict(game),
+                    home_team_score: game.result.home_team_score,
+                    away_team_score: game.result.away_team_score,
+                });
+            }
+        }
+
+        start = end;
+    }
+
+    Backtest { from, to, step, records }
+}
@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{fetch_json, Date};
+
+/// One pitcher's line in a single game.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PitcherAppearance {
+    pub player_id: u32,
+    pub starter: bool,
+    pub outs: u16,
+    pub batters_faced: u16,
+    pub number_of_pitches: u16,
+    pub hits: u16,
+    pub home_runs: u16,
+    pub base_on_balls: u16,
+    pub strike_outs: u16,
+    pub runs: u16,
+    pub earned_runs: u16,
+}
+
+/// Every pitcher a team used in a single game, in order of appearance.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeamPitching {
+    pub team_id: u8,
+    pub appearances: Vec<PitcherAppearance>,
+}
+
+impl TeamPitching {
+    fn from_value(team: &serde_json::Value) -> Result<Self, String> {
+        let Some(team_id) = team["team"]["id"].as_u64() else {
+            return Err("Failed to parse boxscore team".to_string());
+        };
+        let Some(pitchers) = team["pitchers"].as_array() else {
+            return Err("Failed to parse boxscore pitchers".to_string());
+        };
+
+        let appearances = pitchers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, player_id)| {
+                let player_id = player_id.as_u64()?;
+                let stat = &team["players"][format!("ID{player_id}")]["stats"]["pitching"];
+                let count = |key: &str| stat[key].as_u64().unwrap_or(0) as u16;
+
+                Some(PitcherAppearance {
+                    player_id: player_id as u32,
+                    starter: i == 0,
+                    outs: count("outs"),
+                    batters_faced: count("battersFaced"),
+                    number_of_pitches: count("numberOfPitches"),
+                    hits: count("hits"),
+                    home_runs: count("homeRuns"),
+                    base_on_balls: count("baseOnBalls"),
+                    strike_outs: count("strikeOuts"),
+                    runs: count("runs"),
+                    earned_runs: count("earnedRuns"),
+                })
+            })
+            .collect();
+
+        Ok(TeamPitching {
+            team_id: team_id as u8,
+            appearances,
+        })
+    }
+}
+
+/// The pitching lines of both teams in a single game, stored in `data/boxscores/<gamePk>.json`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GamePitching {
+    pub game_pk: u32,
+    pub date: Date,
+    pub home: TeamPitching,
+    pub away: TeamPitching,
+}
+
+impl GamePitching {
+    /// Load the game's pitching lines from disk, fetching and saving them first if they haven't been stored yet.
+    pub async fn get(game_pk: u32, date: &Date) -> Result<Self, String> {
+        if let Some(game_pitching) = GamePitching::load(game_pk) {
+            return Ok(game_pitching);
+        }
+
+        let game_pitching = GamePitching::fetch(game_pk, date).await?;
+        game_pitching.save()?;
+
+        Ok(game_pitching)
+    }
+
+    /// Get the pitching lines of the given team in this game, if it played in it.
+    pub fn team(&self, team_id: u8) -> Option<&TeamPitching> {
+        if self.home.team_id == team_id {
+            Some(&self.home)
+        } else if self.away.team_id == team_id {
+            Some(&self.away)
+        } else {
+            None
+        }
+    }
+
+    async fn fetch(game_pk: u32, date: &Date) -> Result<Self, String> {
+        let url = format!("https://statsapi.mlb.com/api/v1/game/{}/boxscore", game_pk);
+        let boxscore = fetch_json(&url).await?;
+
+        Ok(GamePitching {
+            game_pk,
+            date: *date,
+            home: TeamPitching::from_value(&boxscore["teams"]["home"])?,
+            away: TeamPitching::from_value(&boxscore["teams"]["away"])?,
+        })
+    }
+
+    fn file_path(game_pk: u32) -> String {
+        format!("data/boxscores/{}.json", game_pk)
+    }
+
+    fn load(game_pk: u32) -> Option<Self> {
+        let json = std::fs::read_to_string(GamePitching::file_path(game_pk)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        std::fs::create_dir_all("data/boxscores")
+            .map_err(|e| format!("Failed to create directories: {}", e))?;
+
+        let file_path = GamePitching::file_path(self.game_pk);
+        let json = serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize game pitching: {}", e))?;
+        std::fs::write(&file_path, json)
+            .map_err(|e| format!("Failed to write file {}: {}", file_path, e))?;
+
+        Ok(())
+    }
+}
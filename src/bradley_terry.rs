@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use crate::model::Game;
+use crate::models::linalg::cholesky_solve;
+use crate::ratings::Ratings;
+
+/// The ridge penalty on every team's strength: one over the variance of teams' strengths (in log odds), which makes
+/// the fit the posterior mode under a normal prior.
+const PENALTY: f64 = 12.0;
+
+/// The maximum number of Newton steps when fitting.
+const MAX_ITERATIONS: usize = 25;
+
+/// The largest change in a strength below which the fit has converged.
+const TOLERANCE: f64 = 1e-8;
+
+/// A Bradley–Terry model of who wins: the log odds of the home team winning are the home-field advantage plus the
+/// home team's strength minus the away team's.
+#[derive(Debug, Clone)]
+pub struct BradleyTerry {
+    pub home_advantage: f64,
+    pub strengths: HashMap<u8, f64>,
+}
+
+fn logistic(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+impl BradleyTerry {
+    /// Fit the model to the given games by penalized maximum likelihood, with Newton's method.
+    pub fn fit<'a>(games: impl IntoIterator<Item = &'a Game>) -> Option<Self> {
+        let games: Vec<&Game> = games
+            .into_iter()
+            .filter(|game| game.result.home_team_score != game.result.away_team_score)
+            .collect();
+        if games.is_empty() {
+            return None;
+        }
+
+        // the home-field advantage is parameter 0, then each team's strength
+        let mut indices: HashMap<u8, usize> = HashMap::new();
+        for game in &games {
+            for team_id in [game.home_team.team_id, game.away_team.team_id] {
+                let index = 1 + indices.len();
+                indices.entry(team_id).or_insert(index);
+            }
+        }
+        let size = 1 + indices.len();
+
+        let mut parameters = vec![0.0; size];
+        for _ in 0..MAX_ITERATIONS {
+            let mut gradient: Vec<f64> = parameters.iter().enumerate().map(|(i, parameter)| if i > 0 { -PENALTY * parameter } else { 0.0 }).collect();
+            let mut hessian = vec![0.0; size * size];
+            for i in 1..size {
+                hessian[i * size + i] = PENALTY;
+            }
+            // a tiny penalty on the home-field advantage keeps the system solvable with only a few games
+            hessian[0] = 1e-6;
+
+            for game in &games {
+                let home = indices[&game.home_team.team_id];
+                let away = indices[&game.away_team.team_id];
+                let probability = logistic(parameters[0] + parameters[home] - parameters[away]);
+                let home_won = if game.result.home_team_score > game.result.away_team_score { 1.0 } else { 0.0 };
+
+                let terms = [(0, 1.0), (home, 1.0), (away, -1.0)];
+                for (i, x_i) in terms {
+                    gradient[i] += (home_won - probability) * x_i;
+                    for (j, x_j) in terms {
+                        hessian[i * size + j] += probability * (1.0 - probability) * x_i * x_j;
+                    }
+                }
+            }
+
+            let step = cholesky_solve(&hessian, &gradient).ok()?;
+            for (parameter, step) in parameters.iter_mut().zip(&step) {
+                *parameter += step;
+            }
+            if step.iter().all(|step| step.abs() < TOLERANCE) {
+                break;
+            }
+        }
+
+        Some(BradleyTerry {
+            home_advantage: parameters[0],
+            strengths: indices.into_iter().map(|(team_id, index)| (team_id, parameters[index])).collect(),
+        })
+    }
+
+    /// A team's strength, or an average one if it hasn't been seen.
+    pub fn strength(&self, team_id: u8) -> f64 {
+        self.strengths.get(&team_id).copied().unwrap_or(0.0)
+    }
+
+    /// The probability that the home team beats the away team.
+    pub fn home_win_probability(&self, home_team_id: u8, away_team_id: u8) -> f64 {
+        logistic(self.home_advantage + self.strength(home_team_id) - self.strength(away_team_id))
+    }
+}
+
+/// Set both teams' Bradley–Terry strengths going into every game, from the season's games before its date.
+///
+/// `games` must be in chronological order.
+pub fn assign_bradley_terry(games: &mut [Game]) {
+    let mut start = 0;
+    let mut season_start = 0;
+    while start < games.len() {
+        let date = games[start].setting.date;
+        let end = start + games[start..].iter().take_while(|game| game.setting.date == date).count();
+        if games[season_start].setting.date.year != date.year {
+            season_start = start;
+        }
+
+        let model = BradleyTerry::fit(&games[season_start..start]);
+        for game in games[start..end].iter_mut() {
+            for team in [&mut game.home_team, &mut game.away_team] {
+                team.ratings.get_or_insert_with(Ratings::default).bradley_terry = model.as_ref().map(|model| model.strength(team.team_id) as f32);
+            }
+        }
+
+        start = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "{} != {}", actual, expected);
+    }
+
+    /// Team 1 hosts team 2 four times and wins `home_wins` of them, then visits four times and wins `road_wins`.
+    fn series(home_wins: usize, road_wins: usize) -> Vec<Game> {
+        let mut games = Vec::new();
+        for day in 0..4 {
+            let (home, away) = if day < home_wins { (5, 3) } else { (3, 5) };
+            games.push(Game::with_score(&format!("2024-04-0{}", day + 1), 1, 2, home, away));
+        }
+        for day in 0..4 {
+            let (home, away) = if day < road_wins { (3, 5) } else { (5, 3) };
+            games.push(Game::with_score(&format!("2024-04-1{}", day + 1), 2, 1, home, away));
+        }
+
+        games
+    }
+
+    #[test]
+    fn converges_on_a_known_record() {
+        // even teams, and the home team wins 3 of 4: odds of 3
+        let model = BradleyTerry::fit(&series(3, 1)).unwrap();
+        assert_close(model.home_advantage, 3f64.ln());
+        assert_close(model.strength(1), 0.0);
+        assert_close(model.strength(2), 0.0);
+
+        // team 1 is better: its wins above the model's expectation are balanced by the penalty on its strength
+        let games = series(3, 2);
+        let model = BradleyTerry::fit(&games).unwrap();
+        assert!(model.strength(1) > 0.0);
+        let mut home_residual = 0.0;
+        let mut team_1_residual = 0.0;
+        for game in &games {
+            let home_won = if game.result.home_team_score > game.result.away_team_score { 1.0 } else { 0.0 };
+            let residual = home_won - model.home_win_probability(game.home_team.team_id, game.away_team.team_id);
+            home_residual += residual;
+            team_1_residual += if game.home_team.team_id == 1 { residual } else { -residual };
+        }
+        assert_close(home_residual, 0.0);
+        assert_close(team_1_residual, PENALTY * model.strength(1));
+        assert_close(model.strength(1) + model.strength(2), 0.0);
+    }
+
+    #[test]
+    fn strengths_come_from_earlier_dates() {
+        let mut games = series(3, 2);
+        games.push(Game::with_score("2024-04-20", 1, 2, 0, 0));
+        assign_bradley_terry(&mut games);
+
+        assert_eq!(games[0].home_team.ratings.as_ref().unwrap().bradley_terry, None);
+        let model = BradleyTerry::fit(&games[..8]).unwrap();
+        assert_eq!(games[8].home_team.ratings.as_ref().unwrap().bradley_terry, Some(model.strength(1) as f32));
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use crate::boxscore::GamePitching;
+use crate::model::{fetch_json, Date};
+use crate::pitcher::PitchingLine;
+
+/// Relief usage over the last few days before a game.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BullpenWorkload {
+    pub appearances: u16,
+    pub number_of_pitches: u16,
+}
+
+/// A team's relievers, separated from its starters, as of a game.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Bullpen {
+    /// The combined line of all relief appearances over the 30 days before the game.
+    pub stats: PitchingLine,
+    pub last_1_day: BullpenWorkload,
+    pub last_3_days: BullpenWorkload,
+    pub last_7_days: BullpenWorkload,
+}
+
+impl Bullpen {
+    /// Get the team's bullpen stats and workload over the 30 days before `date`, from the stored pitching lines
+    /// of its games in that window (fetching any that haven't been stored yet).
+    ///
+    /// Games whose pitching lines can't be fetched are left out rather than failing the whole snapshot.
+    pub async fn by_date(team_id: u8, date: &Date) -> Result<Self, String> {
+        let url = format!(
+            "https://statsapi.mlb.com/api/v1/schedule?sportId=1&teamId={}&startDate={}&endDate={}",
+            team_id,
+            date.minus_n_days(30),
+            date.previous_day(),
+        );
+        let schedule = fetch_json(&url).await?;
+
+        let scheduled_games = schedule["dates"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|date| date["games"].as_array().into_iter().flatten());
+
+        let mut games = Vec::new();
+        for scheduled_game in scheduled_games {
+            if scheduled_game["status"]["statusCode"].as_str() != Some("F") {
+                continue;
+            }
+            let (Some(game_pk), Some(game_date)) = (scheduled_game["gamePk"].as_u64(), scheduled_game["officialDate"].as_str()) else {
+                continue;
+            };
+            if let Ok(game_pitching) = GamePitching::get(game_pk as u32, &Date::from(game_date)).await {
+                games.push(game_pitching);
+            }
+        }
+
+        let relief_appearances = |since: Date| games
+            .iter()
+            .filter(move |game| game.date >= since)
+            .filter_map(move |game| game.team(team_id))
+            .flat_map(|team| team.appearances.iter())
+            .filter(|appearance| !appearance.starter);
+        let workload = |days: u8| {
+            let mut workload = BullpenWorkload::default();
+            for appearance in relief_appearances(date.minus_n_days(days)) {
+                workload.appearances += 1;
+                workload.number_of_pitches += appearance.number_of_pitches;
+            }
+            workload
+        };
+
+        Ok(Bullpen {
+            stats: PitchingLine::from_appearances(relief_appearances(date.minus_n_days(30))),
+            last_1_day: workload(1),
+            last_3_days: workload(3),
+            last_7_days: workload(7),
+        })
+    }
+}
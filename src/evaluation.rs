@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+
+use crate::backtest::BacktestRecord;
+
+/// The number of equal-width bins of predicted probability in a calibration table.
+pub const CALIBRATION_BINS: usize = 10;
+
+/// The smallest expected runs used in the Poisson deviance, which is infinite for a prediction of zero or less.
+const MIN_EXPECTED_RUNS: f64 = 1e-9;
+
+pub fn root_mean_squared_error(actual: &[f64], predicted: &[f64]) -> f64 {
+    (actual.iter().zip(predicted).map(|(y, p)| (y - p).powi(2)).sum::<f64>() / actual.len() as f64).sqrt()
+}
+
+pub fn mean_absolute_error(actual: &[f64], predicted: &[f64]) -> f64 {
+    actual.iter().zip(predicted).map(|(y, p)| (y - p).abs()).sum::<f64>() / actual.len() as f64
+}
+
+/// The coefficient of determination of a set of predictions.
+pub fn r2_score(actual: &[f64], predicted: &[f64]) -> f64 {
+    let mean = actual.iter().sum::<f64>() / actual.len() as f64;
+    let residual: f64 = actual.iter().zip(predicted).map(|(y, p)| (y - p).powi(2)).sum();
+    let total: f64 = actual.iter().map(|y| (y - mean).powi(2)).sum();
+
+    1.0 - residual / total
+}
+
+/// The mean Poisson deviance of predicted expected counts, which (unlike the squared error) judges them as the
+/// means of the counts' distributions.
+pub fn poisson_deviance(actual: &[f64], predicted: &[f64]) -> f64 {
+    let deviance: f64 = actual
+        .iter()
+        .zip(predicted)
+        .map(|(y, p)| {
+            let p = p.max(MIN_EXPECTED_RUNS);
+            let log_term = if *y > 0.0 { y * (y / p).ln() } else { 0.0 };
+            2.0 * (log_term - (y - p))
+        })
+        .sum();
+
+    deviance / actual.len() as f64
+}
+
+/// The mean negative log likelihood of the outcomes (1 if it happened, 0 if not) under their predicted probabilities.
+pub fn log_loss(outcomes: &[f64], probabilities: &[f64]) -> f64 {
+    let loss: f64 = outcomes
+        .iter()
+        .zip(probabilities)
+        .map(|(outcome, probability)| {
+            let probability = probability.clamp(1e-15, 1.0 - 1e-15);
+            -(outcome * probability.ln() + (1.0 - outcome) * (1.0 - probability).ln())
+        })
+        .sum();
+
+    loss / outcomes.len() as f64
+}
+
+/// The mean squared difference between the outcomes and their predicted probabilities.
+pub fn brier_score(outcomes: &[f64], probabilities: &[f64]) -> f64 {
+    outcomes.iter().zip(probabilities).map(|(outcome, probability)| (outcome - probability).powi(2)).sum::<f64>() / outcomes.len() as f64
+}
+
+/// The ranked probability score of a distribution over counts (0, 1, 2, ...) given the count that happened: the mean
+/// squared difference between its cumulative distribution and the outcome's. Counts beyond the distribution are taken
+/// as its last one.
+pub fn ranked_probability_score(distribution: &[f64], outcome: usize) -> f64 {
+    if distribution.len() < 2 {
+        return 0.0;
+    }
+
+    let outcome = outcome.min(distribution.len() - 1);
+    let mut cumulative = 0.0;
+    let mut score = 0.0;
+    for (count, probability) in distribution.iter().enumerate().take(distribution.len() - 1) {
+        cumulative += probability;
+        let observed = if outcome <= count { 1.0 } else { 0.0 };
+        score += (cumulative - observed).powi(2);
+    }
+
+    score / (distribution.len() - 1) as f64
+}
+
+/// The predictions whose probabilities fell in one bin of a calibration table, and how often their event happened.
+/// Plotting `observed_frequency` against `mean_probability` for every bin gives the reliability curve.
+#[derive(Debug, Clone)]
+pub struct CalibrationBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+    pub mean_probability: f64,
+    pub observed_frequency: f64,
+}
+
+/// Group the predicted probabilities into [`CALIBRATION_BINS`] equal-width bins, leaving out empty ones.
+pub fn calibration_table(outcomes: &[f64], probabilities: &[f64]) -> Vec<CalibrationBin> {
+    let mut sums = [(0usize, 0.0, 0.0); CALIBRATION_BINS];
+    for (outcome, probability) in outcomes.iter().zip(probabilities) {
+        let bin = ((probability * CALIBRATION_BINS as f64) as usize).min(CALIBRATION_BINS - 1);
+        sums[bin].0 += 1;
+        sums[bin].1 += probability;
+        sums[bin].2 += outcome;
+    }
+
+    sums.iter()
+        .enumerate()
+        .filter(|(_, (count, _, _))| *count > 0)
+        .map(|(bin, (count, probability_sum, outcome_sum))| CalibrationBin {
+            lower: bin as f64 / CALIBRATION_BINS as f64,
+            upper: (bin + 1) as f64 / CALIBRATION_BINS as f64,
+            count: *count,
+            mean_probability: probability_sum / *count as f64,
+            observed_frequency: outcome_sum / *count as f64,
+        })
+        .collect()
+}
+
+/// How well a model's expected runs for one team in each game match the runs it scored.
+#[derive(Debug, Clone)]
+pub struct RunMetrics {
+    pub rmse: f64,
+    pub mae: f64,
+    pub r2: f64,
+    pub poisson_deviance: f64,
+}
+
+impl RunMetrics {
+    fn new(actual: &[f64], predicted: &[f64]) -> Option<Self> {
+        if actual.is_empty() {
+            return None;
+        }
+
+        Some(RunMetrics {
+            rmse: root_mean_squared_error(actual, predicted),
+            mae: mean_absolute_error(actual, predicted),
+            r2: r2_score(actual, predicted),
+            poisson_deviance: poisson_deviance(actual, predicted),
+        })
+    }
+}
+
+/// How well a model's home win probabilities match the winners, over the games that weren't tied.
+#[derive(Debug, Clone)]
+pub struct WinMetrics {
+    pub games: usize,
+    pub log_loss: f64,
+    pub brier_score: f64,
+    pub accuracy: f64,
+    pub calibration: Vec<CalibrationBin>,
+}
+
+/// Every metric a set of backtest predictions can be scored on. Each is `None` if none of the predictions include
+/// what it needs.
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    pub predictions: usize,
+    pub home_runs: Option<RunMetrics>,
+    pub away_runs: Option<RunMetrics>,
+    pub win: Option<WinMetrics>,
+    /// The mean ranked probability score of the distributions of total runs.
+    pub total_runs_rps: Option<f64>,
+}
+
+impl Evaluation {
+    pub fn new<'a>(records: impl IntoIterator<Item = &'a BacktestRecord>) -> Self {
+        let records: Vec<&BacktestRecord> = records.into_iter().collect();
+
+        let runs = |expected: fn(&BacktestRecord) -> Option<f64>, actual: fn(&BacktestRecord) -> u8| {
+            let (actual, predicted): (Vec<f64>, Vec<f64>) = records
+                .iter()
+                .filter_map(|record| expected(record).map(|expected| (actual(record) as f64, expected)))
+                .unzip();
+            RunMetrics::new(&actual, &predicted)
+        };
+
+        let (outcomes, probabilities): (Vec<f64>, Vec<f64>) = records
+            .iter()
+            .filter(|record| record.home_team_score != record.away_team_score)
+            .filter_map(|record| {
+                let home_won = if record.home_team_score > record.away_team_score { 1.0 } else { 0.0 };
+                record.prediction.home_win_probability.map(|probability| (home_won, probability))
+            })
+            .unzip();
+        let win = (!outcomes.is_empty()).then(|| WinMetrics {
+            games: outcomes.len(),
+            log_loss: log_loss(&outcomes, &probabilities),
+            brier_score: brier_score(&outcomes, &probabilities),
+            accuracy: outcomes.iter().zip(&probabilities).filter(|(outcome, probability)| (**probability > 0.5) == (**outcome == 1.0)).count() as f64
+                / outcomes.len() as f64,
+            calibration: calibration_table(&outcomes, &probabilities),
+        });
+
+        let scores: Vec<f64> = records
+            .iter()
+            .filter_map(|record| {
+                let distribution = record.prediction.total_runs_probabilities.as_ref()?;
+                Some(ranked_probability_score(distribution, (record.home_team_score + record.away_team_score) as usize))
+            })
+            .collect();
+        let total_runs_rps = (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64);
+
+        Evaluation {
+            predictions: records.len(),
+            home_runs: runs(|record| record.prediction.expected_home_runs, |record| record.home_team_score),
+            away_runs: runs(|record| record.prediction.expected_away_runs, |record| record.away_team_score),
+            win,
+            total_runs_rps,
+        }
+    }
+}
+
+/// How backtest predictions are grouped to be evaluated separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    Season,
+    /// The calendar month, over every season.
+    Month,
+    /// Each team's games, home and away, so every game is in two groups.
+    Team,
+}
+
+impl std::str::FromStr for Grouping {
+    type Err = String;
+
+    fn from_str(grouping: &str) -> Result<Self, String> {
+        match grouping {
+            "season" => Ok(Grouping::Season),
+            "month" => Ok(Grouping::Month),
+            "team" => Ok(Grouping::Team),
+            _ => Err(format!("Unknown grouping {}, expected season, month or team", grouping)),
+        }
+    }
+}
+
+/// Evaluate the predictions of each group separately, in the order of the groups.
+pub fn evaluate_by(records: &[BacktestRecord], grouping: Grouping) -> BTreeMap<u16, Evaluation> {
+    let mut groups: BTreeMap<u16, Vec<&BacktestRecord>> = BTreeMap::new();
+    for record in records {
+        let keys = match grouping {
+            Grouping::Season => vec![record.date.year],
+            Grouping::Month => vec![record.date.month as u16],
+            Grouping::Team => vec![record.home_team_id as u16, record.away_team_id as u16],
+        };
+        for key in keys {
+            groups.entry(key).or_default().push(record);
+        }
+    }
+
+    groups.into_iter().map(|(key, records)| (key, Evaluation::new(records))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn log_loss_textbook_values() {
+        assert_close(log_loss(&[1.0, 0.0], &[0.5, 0.5]), std::f64::consts::LN_2);
+        assert_close(log_loss(&[1.0, 0.0], &[0.8, 0.3]), -(0.8f64.ln() + 0.7f64.ln()) / 2.0);
+        // certain and wrong is clamped rather than infinite
+        assert!(log_loss(&[1.0], &[0.0]).is_finite());
+    }
+
+    #[test]
+    fn ranked_probability_score_textbook_values() {
+        // cumulative 0.2, 0.7 against the outcome's 0, 1
+        assert_close(ranked_probability_score(&[0.2, 0.5, 0.3], 1), (0.04 + 0.09) / 2.0);
+        assert_close(ranked_probability_score(&[0.0, 1.0, 0.0], 1), 0.0);
+        // all the probability on the wrong end
+        assert_close(ranked_probability_score(&[1.0, 0.0, 0.0], 2), 1.0);
+        // counts beyond the distribution are its last one
+        assert_close(ranked_probability_score(&[0.2, 0.5, 0.3], 10), ranked_probability_score(&[0.2, 0.5, 0.3], 2));
+    }
+
+    #[test]
+    fn brier_score_and_regression_metrics() {
+        assert_close(brier_score(&[1.0, 0.0], &[0.8, 0.3]), (0.04 + 0.09) / 2.0);
+        assert_close(root_mean_squared_error(&[1.0, 2.0, 3.0], &[1.0, 2.0, 5.0]), (4.0f64 / 3.0).sqrt());
+        assert_close(mean_absolute_error(&[1.0, 2.0, 3.0], &[1.0, 2.0, 5.0]), 2.0 / 3.0);
+        assert_close(r2_score(&[1.0, 2.0, 3.0], &[1.0, 2.0, 3.0]), 1.0);
+    }
+
+    #[test]
+    fn unknown_groupings_are_errors() {
+        assert!(matches!("team".parse::<Grouping>(), Ok(Grouping::Team)));
+        assert_eq!("week".parse::<Grouping>().unwrap_err(), "Unknown grouping week, expected season, month or team");
+    }
+}
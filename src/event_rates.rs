@@ -0,0 +1,138 @@
+use crate::model::{Game, HittingStats, PitchingStats};
+
+/// The outcomes of a plate appearance that the simulators distinguish.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// A walk or hit by pitch.
+    Walk,
+    Single,
+    Double,
+    Triple,
+    HomeRun,
+    Strikeout,
+    /// Any other out, including sacrifices and reaching on an error.
+    OutInPlay,
+}
+
+pub const EVENTS: [Event; 7] = [
+    Event::Walk,
+    Event::Single,
+    Event::Double,
+    Event::Triple,
+    Event::HomeRun,
+    Event::Strikeout,
+    Event::OutInPlay,
+];
+
+/// The probability of each [`Event`] per plate appearance.
+#[derive(Debug, Clone, Copy)]
+pub struct EventRates {
+    pub probabilities: [f64; 7],
+}
+
+impl EventRates {
+    /// Rough MLB averages over 2016-2021.
+    pub const LEAGUE_AVERAGE: EventRates = EventRates {
+        probabilities: [0.095, 0.143, 0.045, 0.004, 0.033, 0.225, 0.455],
+    };
+
+    pub fn probability(&self, event: Event) -> f64 {
+        self.probabilities[event as usize]
+    }
+
+    /// Compute the rates from counts of each event, or `None` if there were no plate appearances.
+    fn from_counts(plate_appearances: f64, walks: f64, hits: f64, doubles: f64, triples: f64, home_runs: f64, strikeouts: f64) -> Option<Self> {
+        if plate_appearances <= 0.0 {
+            return None;
+        }
+
+        let singles = (hits - doubles - triples - home_runs).max(0.0);
+        let mut probabilities = [walks, singles, doubles, triples, home_runs, strikeouts, 0.0].map(|count| count / plate_appearances);
+        probabilities[Event::OutInPlay as usize] = (1.0 - probabilities.iter().sum::<f64>()).max(0.0);
+
+        Some(EventRates { probabilities }.normalized())
+    }
+
+    /// A lineup's rates, from its batting stats.
+    pub fn from_hitting(stats: &HittingStats) -> Option<Self> {
+        EventRates::from_counts(
+            stats.plate_appearances as f64,
+            (stats.base_on_balls + stats.hit_by_pitch) as f64,
+            stats.hits as f64,
+            stats.doubles as f64,
+            stats.triples as f64,
+            stats.home_runs as f64,
+            stats.strike_outs as f64,
+        )
+    }
+
+    /// The rates allowed by a pitching staff, from its pitching stats.
+    pub fn from_pitching(stats: &PitchingStats) -> Option<Self> {
+        EventRates::from_counts(
+            stats.batters_faced as f64,
+            (stats.base_on_balls + stats.hit_by_pitch) as f64,
+            stats.hits as f64,
+            stats.doubles as f64,
+            stats.triples as f64,
+            stats.home_runs as f64,
+            stats.strike_outs as f64,
+        )
+    }
+
+    /// The league's rates, from the pitching stats of every team snapshot in the given games.
+    pub fn league<'a>(games: impl IntoIterator<Item = &'a Game>) -> Self {
+        let mut counts = [0.0; 7];
+        let mut batters_faced = 0.0;
+        for team in games.into_iter().flat_map(|game| [&game.home_team, &game.away_team]) {
+            let Some(rates) = EventRates::from_pitching(&team.pitching_stats) else {
+                continue;
+            };
+            for (count, probability) in counts.iter_mut().zip(rates.probabilities) {
+                *count += probability * team.pitching_stats.batters_faced as f64;
+            }
+            batters_faced += team.pitching_stats.batters_faced as f64;
+        }
+        if batters_faced == 0.0 {
+            return EventRates::LEAGUE_AVERAGE;
+        }
+
+        EventRates {
+            probabilities: counts.map(|count| count / batters_faced),
+        }
+    }
+
+    /// Combine a lineup's rates with the rates allowed by the opposing pitchers, relative to the league, using the
+    /// generalized log5 (odds ratio) method.
+    pub fn matchup(batting: &EventRates, pitching: &EventRates, league: &EventRates) -> Self {
+        let mut probabilities = [0.0; 7];
+        for event in EVENTS {
+            let league_rate = league.probability(event);
+            if league_rate > 0.0 {
+                probabilities[event as usize] = batting.probability(event) * pitching.probability(event) / league_rate;
+            }
+        }
+
+        EventRates { probabilities }.normalized()
+    }
+
+    /// The rates a team's lineup can expect against the opposing team's pitching staff.
+    ///
+    /// Falls back to the league rates for a side with no plate appearances.
+    pub fn for_game(batting: &HittingStats, pitching: &PitchingStats, league: &EventRates) -> Self {
+        let batting = EventRates::from_hitting(batting).unwrap_or(*league);
+        let pitching = EventRates::from_pitching(pitching).unwrap_or(*league);
+
+        EventRates::matchup(&batting, &pitching, league)
+    }
+
+    fn normalized(self) -> Self {
+        let total: f64 = self.probabilities.iter().sum();
+        if total <= 0.0 {
+            return EventRates::LEAGUE_AVERAGE;
+        }
+
+        EventRates {
+            probabilities: self.probabilities.map(|probability| probability / total),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Game, HittingStats, PitchingStats, Team};
+
+/// The power of runs per game in the Pythagenpat exponent.
+const PYTHAGENPAT_POWER: f32 = 0.287;
+
+/// A team's expected winning percentage and runs from its stats in the snapshot window, which tend to predict its
+/// future better than its actual record and runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpectedWins {
+    /// The Pythagenpat exponent for the team's run environment.
+    pub pythagenpat_exponent: Option<f32>,
+    /// The expected winning percentage from runs scored and allowed.
+    pub pythagorean_win_percentage: Option<f32>,
+    /// The runs per game the team's hitting components are worth.
+    pub base_runs_per_game: Option<f32>,
+    /// The runs per game the hitting components its pitchers allowed are worth.
+    pub base_runs_allowed_per_game: Option<f32>,
+    /// The expected winning percentage from BaseRuns scored and allowed.
+    pub base_runs_win_percentage: Option<f32>,
+}
+
+/// The Pythagenpat exponent for the given runs scored and allowed per game.
+fn pythagenpat_exponent(runs_per_game: f32, runs_allowed_per_game: f32) -> f32 {
+    (runs_per_game + runs_allowed_per_game).powf(PYTHAGENPAT_POWER)
+}
+
+/// The expected winning percentage for the given runs scored and allowed per game, or `None` if neither team scored.
+fn pythagenpat(runs_per_game: f32, runs_allowed_per_game: f32) -> Option<f32> {
+    if runs_per_game + runs_allowed_per_game <= 0.0 {
+        return None;
+    }
+
+    let exponent = pythagenpat_exponent(runs_per_game, runs_allowed_per_game);
+    let runs = runs_per_game.powf(exponent);
+    Some(runs / (runs + runs_allowed_per_game.powf(exponent)))
+}
+
+/// David Smyth's BaseRuns: baserunners times the rate at which they score, plus home runs.
+fn base_runs(baserunners: f32, advancement: f32, outs: f32, home_runs: f32) -> Option<f32> {
+    if advancement + outs <= 0.0 {
+        return None;
+    }
+
+    Some(baserunners * advancement / (advancement + outs) + home_runs)
+}
+
+/// The BaseRuns of a lineup's hitting stats.
+fn batting_base_runs(stats: &HittingStats) -> Option<f32> {
+    let at_bats = stats.at_bats as f32;
+    let hits = stats.hits as f32;
+    let total_bases = stats.total_bases as f32;
+    let home_runs = stats.home_runs as f32;
+    let walks = (stats.base_on_balls + stats.hit_by_pitch) as f32;
+    let lost_runners = (stats.caught_stealing + stats.ground_into_double_play) as f32;
+
+    base_runs(
+        hits + walks - home_runs - 0.5 * stats.intentional_walks as f32,
+        (1.4 * total_bases - 0.6 * hits - 3.0 * home_runs + 0.1 * (walks - stats.intentional_walks as f32)
+            + 0.9 * (stats.stolen_bases as f32 - lost_runners))
+            * 1.1,
+        at_bats - hits + lost_runners,
+        home_runs,
+    )
+}
+
+/// The BaseRuns of the hitting a pitching staff allowed, from the basic formula without baserunning.
+fn pitching_base_runs(stats: &PitchingStats) -> Option<f32> {
+    let at_bats = stats.at_bats as f32;
+    let hits = stats.hits as f32;
+    let home_runs = stats.home_runs as f32;
+    let singles = (hits - (stats.doubles + stats.triples) as f32 - home_runs).max(0.0);
+    let total_bases = singles + 2.0 * stats.doubles as f32 + 3.0 * stats.triples as f32 + 4.0 * home_runs;
+    let walks = (stats.base_on_balls + stats.hit_by_pitch) as f32;
+
+    base_runs(
+        hits + walks - home_runs,
+        (1.4 * total_bases - 0.6 * hits - 3.0 * home_runs + 0.1 * (walks - stats.intentional_walks as f32)) * 1.02,
+        at_bats - hits,
+        home_runs,
+    )
+}
+
+impl ExpectedWins {
+    pub fn from_team(team: &Team) -> Self {
+        let per_game = |total: Option<f32>, games: u16| total.filter(|_| games > 0).map(|total| total / games as f32);
+        let runs_per_game = per_game(Some(team.batting_stats.runs as f32), team.batting_stats.games_played);
+        let runs_allowed_per_game = per_game(Some(team.pitching_stats.runs as f32), team.pitching_stats.games_played);
+        let base_runs_per_game = per_game(batting_base_runs(&team.batting_stats), team.batting_stats.games_played);
+        let base_runs_allowed_per_game = per_game(pitching_base_runs(&team.pitching_stats), team.pitching_stats.games_played);
+
+        let both = |scored: Option<f32>, allowed: Option<f32>| scored.zip(allowed);
+        ExpectedWins {
+            pythagenpat_exponent: both(runs_per_game, runs_allowed_per_game).map(|(scored, allowed)| pythagenpat_exponent(scored, allowed)),
+            pythagorean_win_percentage: both(runs_per_game, runs_allowed_per_game).and_then(|(scored, allowed)| pythagenpat(scored, allowed)),
+            base_runs_per_game,
+            base_runs_allowed_per_game,
+            base_runs_win_percentage: both(base_runs_per_game, base_runs_allowed_per_game).and_then(|(scored, allowed)| pythagenpat(scored, allowed)),
+        }
+    }
+}
+
+/// Set both teams' expected wins in every game from their snapshot stats.
+pub fn assign_expected_wins(games: &mut [Game]) {
+    for game in games.iter_mut() {
+        for team in [&mut game.home_team, &mut game.away_team] {
+            team.expected_wins = Some(ExpectedWins::from_team(team));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn assert_close(actual: Option<f32>, expected: f32) {
+        let actual = actual.unwrap();
+        assert!((actual - expected).abs() < 1e-4, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn season_totals_give_the_known_outputs() {
+        let mut games = vec![Game::with_score("2016-10-03", 1, 2, 0, 0)];
+        // the 2016 Cubs scored 808 runs and allowed 556 in 162 games, here with season-sized batting and pitching lines
+        games[0].home_team.batting_stats = HittingStats::from_mlb(&json!({
+            "gamesPlayed": 162, "runs": 808, "atBats": 5503, "hits": 1409, "doubles": 293, "triples": 30, "homeRuns": 199,
+            "totalBases": 2359, "baseOnBalls": 656, "intentionalWalks": 45, "hitByPitch": 77, "stolenBases": 66,
+            "caughtStealing": 34, "groundIntoDoublePlay": 107,
+        }));
+        games[0].home_team.pitching_stats = PitchingStats::from_mlb(&json!({
+            "gamesPlayed": 162, "runs": 556, "atBats": 5400, "hits": 1125, "doubles": 220, "triples": 25, "homeRuns": 163,
+            "baseOnBalls": 495, "intentionalWalks": 30, "hitByPitch": 60,
+        }));
+        assign_expected_wins(&mut games);
+
+        let expected_wins = games[0].home_team.expected_wins.as_ref().unwrap();
+        // ((808 + 556) / 162) ^ 0.287
+        assert_close(expected_wins.pythagenpat_exponent, 1.843151);
+        assert_close(expected_wins.pythagorean_win_percentage, 0.665735);
+        // A = 1409 + 733 - 199 - 22.5, B = 1.1 (1.4 * 2359 - 0.6 * 1409 - 3 * 199 + 0.1 * 688 + 0.9 * (66 - 141)),
+        // C = 5503 - 1409 + 141, and BaseRuns = A B / (B + C) + 199 = 824.93
+        assert_close(expected_wins.base_runs_per_game, 5.092173);
+        assert_close(expected_wins.base_runs_allowed_per_game, 3.505737);
+        assert_close(expected_wins.base_runs_win_percentage, 0.666457);
+
+        // a team without games in its window has none
+        let away = games[0].away_team.expected_wins.as_ref().unwrap();
+        assert!(away.pythagorean_win_percentage.is_none() && away.base_runs_per_game.is_none());
+    }
+}
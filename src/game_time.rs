@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::fetch_json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DayNight {
+    Day,
+    Night,
+}
+
+impl DayNight {
+    pub fn from_mlb(day_night: &str) -> Option<Self> {
+        match day_night {
+            "day" => Some(DayNight::Day),
+            "night" => Some(DayNight::Night),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoubleHeader {
+    No,
+    /// Two games for a single admission.
+    Traditional,
+    /// Two games with separate admissions.
+    Split,
+}
+
+impl DoubleHeader {
+    pub fn from_mlb(double_header: &str) -> Option<Self> {
+        match double_header {
+            "N" => Some(DoubleHeader::No),
+            "Y" => Some(DoubleHeader::Traditional),
+            "S" => Some(DoubleHeader::Split),
+            _ => None,
+        }
+    }
+}
+
+/// The scheduled start time of a game, in the venue's local time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalTime {
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl LocalTime {
+    /// Parse a 12-hour time and its `"AM"`/`"PM"` marker (e.g. `"7:05"`, `"PM"`).
+    fn from_mlb(time: &str, am_pm: &str) -> Option<Self> {
+        let (hour, minute) = time.split_once(':')?;
+        let hour = hour.parse::<u8>().ok()? % 12;
+        let hour = if am_pm == "PM" { hour + 12 } else { hour };
+
+        Some(LocalTime {
+            hour,
+            minute: minute.parse().ok()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeatherCondition {
+    Clear,
+    Sunny,
+    PartlyCloudy,
+    Cloudy,
+    Overcast,
+    Drizzle,
+    Rain,
+    Snow,
+    RoofClosed,
+    Dome,
+}
+
+impl WeatherCondition {
+    fn from_mlb(condition: &str) -> Option<Self> {
+        match condition {
+            "Clear" => Some(WeatherCondition::Clear),
+            "Sunny" => Some(WeatherCondition::Sunny),
+            "Partly Cloudy" => Some(WeatherCondition::PartlyCloudy),
+            "Cloudy" => Some(WeatherCondition::Cloudy),
+            "Overcast" => Some(WeatherCondition::Overcast),
+            "Drizzle" => Some(WeatherCondition::Drizzle),
+            "Rain" => Some(WeatherCondition::Rain),
+            "Snow" => Some(WeatherCondition::Snow),
+            "Roof Closed" => Some(WeatherCondition::RoofClosed),
+            "Dome" => Some(WeatherCondition::Dome),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindDirection {
+    Calm,
+    Varies,
+    OutToLeft,
+    OutToCenter,
+    OutToRight,
+    InFromLeft,
+    InFromCenter,
+    InFromRight,
+    LeftToRight,
+    RightToLeft,
+}
+
+impl WindDirection {
+    fn from_mlb(direction: &str) -> Option<Self> {
+        match direction {
+            "None" | "Calm" => Some(WindDirection::Calm),
+            "Varies" => Some(WindDirection::Varies),
+            "Out To LF" => Some(WindDirection::OutToLeft),
+            "Out To CF" => Some(WindDirection::OutToCenter),
+            "Out To RF" => Some(WindDirection::OutToRight),
+            "In From LF" => Some(WindDirection::InFromLeft),
+            "In From CF" => Some(WindDirection::InFromCenter),
+            "In From RF" => Some(WindDirection::InFromRight),
+            "L To R" => Some(WindDirection::LeftToRight),
+            "R To L" => Some(WindDirection::RightToLeft),
+            _ => None,
+        }
+    }
+}
+
+/// The weather at the start of a game, as reported by MLB.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Weather {
+    pub condition: Option<WeatherCondition>,
+    /// Temperature in degrees Fahrenheit.
+    pub temperature: Option<i16>,
+    /// Wind speed in miles per hour.
+    pub wind_speed: Option<u8>,
+    pub wind_direction: Option<WindDirection>,
+}
+
+impl Weather {
+    fn from_mlb(weather: &serde_json::Value) -> Self {
+        // e.g. "7 mph, Out To CF"
+        let wind = weather["wind"].as_str().unwrap_or("");
+        let (wind_speed, wind_direction) = wind.split_once(", ").unwrap_or(("", wind));
+
+        Weather {
+            condition: weather["condition"].as_str().and_then(WeatherCondition::from_mlb),
+            temperature: weather["temp"].as_str().and_then(|temp| temp.parse().ok()),
+            wind_speed: wind_speed.split(' ').next().and_then(|speed| speed.parse().ok()),
+            wind_direction: WindDirection::from_mlb(wind_direction),
+        }
+    }
+}
+
+/// The parts of a game's context that are only in its live feed rather than the schedule.
+pub struct GameTime {
+    pub start_time: Option<LocalTime>,
+    pub weather: Option<Weather>,
+}
+
+impl GameTime {
+    pub async fn fetch(game_pk: u32) -> Result<Self, String> {
+        let url = format!(
+            "https://statsapi.mlb.com/api/v1.1/game/{}/feed/live?fields=gameData,datetime,time,ampm,weather,condition,temp,wind",
+            game_pk,
+        );
+        let feed = fetch_json(&url).await?;
+        let game_data = &feed["gameData"];
+
+        let start_time = match (game_data["datetime"]["time"].as_str(), game_data["datetime"]["ampm"].as_str()) {
+            (Some(time), Some(am_pm)) => LocalTime::from_mlb(time, am_pm),
+            _ => None,
+        };
+        let weather = game_data["weather"].is_object().then(|| Weather::from_mlb(&game_data["weather"]));
+
+        Ok(GameTime { start_time, weather })
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::Game;
+
+/// The number of seasons before a game's own whose meetings count towards [`HeadToHead::recent`].
+const PRIOR_SEASONS: u16 = 2;
+
+/// The two teams' record in their meetings over some period, from the point of view of this game's home team.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MatchupRecord {
+    pub games: u16,
+    pub home_team_wins: u16,
+    pub away_team_wins: u16,
+    /// Runs scored by this game's home team minus runs scored by its away team.
+    pub run_differential: i16,
+    pub average_total_runs: Option<f32>,
+}
+
+/// The two teams' previous meetings, before this game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeadToHead {
+    /// Meetings earlier in this season.
+    pub season: MatchupRecord,
+    /// Meetings earlier in this season and the [`PRIOR_SEASONS`] before it.
+    pub recent: MatchupRecord,
+}
+
+/// Where a game falls in its series, as scheduled: game `game_in_series` of a `games_in_series`-game series.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SeriesContext {
+    /// 1 for the first game of the series.
+    pub game_in_series: u8,
+    pub games_in_series: u8,
+}
+
+impl SeriesContext {
+    /// Read the series context of a game's entry in the MLB schedule, which is known before the game.
+    pub fn from_mlb(game: &serde_json::Value) -> Option<Self> {
+        Some(SeriesContext {
+            game_in_series: game["seriesGameNumber"].as_u64()? as u8,
+            games_in_series: game["gamesInSeries"].as_u64()? as u8,
+        })
+    }
+}
+
+struct Meeting {
+    season: u16,
+    home_team_id: u8,
+    home_team_score: u8,
+    away_team_score: u8,
+}
+
+impl MatchupRecord {
+    fn from_meetings<'a>(home_team_id: u8, meetings: impl Iterator<Item = &'a Meeting>) -> Self {
+        let mut record = MatchupRecord::default();
+        let mut total_runs = 0;
+        for meeting in meetings {
+            // flip meetings where this game's away team was at home
+            let (runs_for, runs_against) = if meeting.home_team_id == home_team_id {
+                (meeting.home_team_score as i16, meeting.away_team_score as i16)
+            } else {
+                (meeting.away_team_score as i16, meeting.home_team_score as i16)
+            };
+
+            record.games += 1;
+            if runs_for > runs_against {
+                record.home_team_wins += 1;
+            } else if runs_against > runs_for {
+                record.away_team_wins += 1;
+            }
+            record.run_differential += runs_for - runs_against;
+            total_runs += runs_for + runs_against;
+        }
+        record.average_total_runs = (record.games > 0).then(|| total_runs as f32 / record.games as f32);
+
+        record
+    }
+}
+
+/// Set the head-to-head record of every game from the stored meetings before it.
+///
+/// `games` must be in chronological order.
+pub fn assign_head_to_head(games: &mut [Game]) {
+    let mut meetings: HashMap<(u8, u8), Vec<Meeting>> = HashMap::new();
+    for game in games.iter_mut() {
+        let home_team_id = game.home_team.team_id;
+        let away_team_id = game.away_team.team_id;
+        let season = game.setting.date.year;
+
+        let pair = meetings.entry((home_team_id.min(away_team_id), home_team_id.max(away_team_id))).or_default();
+        game.head_to_head = Some(HeadToHead {
+            season: MatchupRecord::from_meetings(home_team_id, pair.iter().filter(|meeting| meeting.season == season)),
+            recent: MatchupRecord::from_meetings(home_team_id, pair.iter().filter(|meeting| meeting.season + PRIOR_SEASONS >= season)),
+        });
+
+        pair.push(Meeting {
+            season,
+            home_team_id,
+            home_team_score: game.result.home_team_score,
+            away_team_score: game.result.away_team_score,
+        });
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use indicatif::ProgressIterator;
+
+use crate::bradley_terry;
+use crate::expected_wins;
+use crate::head_to_head;
+use crate::massey;
+use crate::model::Game;
+use crate::park_factor;
+use crate::ratings;
+use crate::rest;
+use crate::strength;
+use crate::venue::Venue;
+
+/// Load every game stored in `data/<season>/<home team id>/<date>.json`, in chronological order.
+pub fn load_games() -> Vec<Game> {
+    let mut paths = Vec::new();
+    for season_dir in std::fs::read_dir("data").into_iter().flatten().flatten() {
+        // skip everything that isn't a season directory (progress.json, boxscores/, ...)
+        if !season_dir.file_name().to_string_lossy().chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        for team_dir in std::fs::read_dir(season_dir.path()).into_iter().flatten().flatten() {
+            for game_file in std::fs::read_dir(team_dir.path()).into_iter().flatten().flatten() {
+                paths.push(game_file.path());
+            }
+        }
+    }
+
+    let mut games = Vec::new();
+    for path in paths.iter().progress() {
+        match Game::load(&path.to_string_lossy()) {
+            Ok(game) => games.push(game),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    games.sort_by_key(|game| (game.setting.date, game.setting.game_number, game.home_team.team_id));
+
+    games
+}
+
+/// Find each team's usual home venue: the one it played the most home games at.
+pub fn home_venues(games: &[Game]) -> HashMap<u8, &Venue> {
+    let mut venue_counts: HashMap<(u8, u16), (&Venue, usize)> = HashMap::new();
+    for game in games {
+        if let Some(venue) = &game.setting.venue {
+            venue_counts.entry((game.home_team.team_id, venue.id)).or_insert((venue, 0)).1 += 1;
+        }
+    }
+
+    let mut home_venues: HashMap<u8, (&Venue, usize)> = HashMap::new();
+    for ((team_id, _), (venue, count)) in venue_counts {
+        let home_venue = home_venues.entry(team_id).or_insert((venue, count));
+        if count > home_venue.1 {
+            *home_venue = (venue, count);
+        }
+    }
+
+    home_venues.into_iter().map(|(team_id, (venue, _))| (team_id, venue)).collect()
+}
+
+/// Get the venue of a game, falling back to the home team's usual venue for games stored before venues were recorded.
+pub fn venue_of<'a>(game: &'a Game, home_venues: &HashMap<u8, &'a Venue>) -> Option<&'a Venue> {
+    game.setting.venue.as_ref().or_else(|| home_venues.get(&game.home_team.team_id).copied())
+}
+
+/// Compute the features of every stored game that aren't fetched with it (mostly ones that depend on the games before
+/// it), and save them.
+pub fn derive_features() {
+    let mut games = load_games();
+
+    // games saved while counting stats were stored as `u8` can have wrapped counts, which some features below use
+    for team in games.iter_mut().flat_map(|game| [&mut game.home_team, &mut game.away_team]) {
+        team.unwrap_counts();
+    }
+
+    let mut old_cutoff_seasons: Vec<u16> = games.iter().filter(|game| !game.has_pregame_stats()).map(|game| game.setting.date.year).collect();
+    if !old_cutoff_seasons.is_empty() {
+        let old_cutoff_games = old_cutoff_seasons.len();
+        old_cutoff_seasons.dedup();
+        let seasons = old_cutoff_seasons.iter().map(|season| season.to_string()).collect::<Vec<_>>().join(", ");
+        eprintln!(
+            "{} games ({}) have team stats through their own date; run `cargo run refetch <season>` for those seasons, \
+            then derive again. They are left out of data.csv and of the backtests that use team stats until then.",
+            old_cutoff_games, seasons
+        );
+    }
+
+    park_factor::assign_park_factors(&mut games);
+    rest::assign_rest(&mut games);
+    head_to_head::assign_head_to_head(&mut games);
+    ratings::assign_elo(&mut games);
+    strength::assign_strengths(&mut games);
+    massey::assign_massey(&mut games);
+    bradley_terry::assign_bradley_terry(&mut games);
+    expected_wins::assign_expected_wins(&mut games);
+
+    for game in games.iter().progress() {
+        if let Err(e) = game.save() {
+            eprintln!("{}", e);
+        }
+    }
+}
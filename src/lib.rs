@@ -0,0 +1,29 @@
+pub mod backtest;
+pub mod boxscore;
+pub mod bradley_terry;
+pub mod bullpen;
+pub mod evaluation;
+pub mod event_rates;
+pub mod expected_wins;
+pub mod game_time;
+pub mod head_to_head;
+pub mod history;
+pub mod linescore;
+pub mod markov;
+pub mod massey;
+pub mod model;
+pub mod models;
+pub mod park_factor;
+pub mod pitcher;
+pub mod plays;
+pub mod prediction;
+pub mod predictors;
+pub mod ratings;
+pub mod rest;
+pub mod rng;
+pub mod run_expectancy;
+pub mod simulation;
+pub mod splits;
+pub mod standings;
+pub mod strength;
+pub mod venue;
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::fetch_json;
+
+/// The number of innings scheduled for a game if the linescore doesn't say.
+pub const DEFAULT_SCHEDULED_INNINGS: u8 = 9;
+
+/// The number of innings counted for first-five-innings targets.
+const FIRST_FIVE_INNINGS: usize = 5;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Score {
+    pub home: u8,
+    pub away: u8,
+}
+
+/// The runs scored by each team in one inning.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct InningScore {
+    pub away: u8,
+    /// `None` if the bottom of the inning wasn't played.
+    pub home: Option<u8>,
+}
+
+/// One team's totals for a game.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LinescoreTotals {
+    pub runs: u8,
+    pub hits: u8,
+    pub errors: u8,
+    pub left_on_base: u8,
+}
+
+impl LinescoreTotals {
+    fn from_mlb(totals: &serde_json::Value) -> Self {
+        let count = |key: &str| totals[key].as_u64().unwrap_or(0) as u8;
+
+        LinescoreTotals {
+            runs: count("runs"),
+            hits: count("hits"),
+            errors: count("errors"),
+            left_on_base: count("leftOnBase"),
+        }
+    }
+}
+
+/// The inning-by-inning scoring of a game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Linescore {
+    pub innings: Vec<InningScore>,
+    pub home: LinescoreTotals,
+    pub away: LinescoreTotals,
+    pub scheduled_innings: u8,
+    pub innings_played: u8,
+    pub extra_innings: bool,
+    /// The score after five innings, if the game got that far.
+    pub first_five: Option<Score>,
+    /// The score after the scheduled innings, leaving out any extra innings (and the runners they start on second).
+    pub regulation: Score,
+}
+
+impl Linescore {
+    pub async fn fetch(game_pk: u32) -> Result<Self, String> {
+        let url = format!("https://statsapi.mlb.com/api/v1/game/{}/linescore", game_pk);
+        let linescore = fetch_json(&url).await?;
+        let Some(innings) = linescore["innings"].as_array() else {
+            return Err(format!("Failed to parse linescore: {url}"));
+        };
+
+        let innings: Vec<InningScore> = innings
+            .iter()
+            .map(|inning| InningScore {
+                away: inning["away"]["runs"].as_u64().unwrap_or(0) as u8,
+                home: inning["home"]["runs"].as_u64().map(|runs| runs as u8),
+            })
+            .collect();
+        let scheduled_innings = linescore["scheduledInnings"].as_u64().map(|innings| innings as u8).unwrap_or(DEFAULT_SCHEDULED_INNINGS);
+
+        let score_through = |count: usize| Score {
+            home: innings.iter().take(count).map(|inning| inning.home.unwrap_or(0)).sum(),
+            away: innings.iter().take(count).map(|inning| inning.away).sum(),
+        };
+
+        Ok(Linescore {
+            first_five: (innings.len() >= FIRST_FIVE_INNINGS).then(|| score_through(FIRST_FIVE_INNINGS)),
+            regulation: score_through(scheduled_innings as usize),
+            home: LinescoreTotals::from_mlb(&linescore["teams"]["home"]),
+            away: LinescoreTotals::from_mlb(&linescore["teams"]["away"]),
+            scheduled_innings,
+            innings_played: innings.len() as u8,
+            extra_innings: innings.len() > scheduled_innings as usize,
+            innings,
+        })
+    }
+}
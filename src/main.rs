@@ -1,28 +1,459 @@
 use std::env;
 use indicatif::{ProgressIterator, ProgressStyle};
 
-mod model;
+use baseball::{backtest, evaluation, history, model, plays};
+use baseball::event_rates::EventRates;
+use baseball::models::{self, count::CountModel, dataset::Dataset, linear::LinearModel, Regressor};
+use baseball::models::boosting::{BoostingParams, GradientBoosting, Loss};
+use baseball::models::{onnx::OnnxModel, parity, xgboost::XGBoostModel};
+use baseball::bradley_terry::BradleyTerry;
+use baseball::massey::Massey;
+use baseball::prediction::{self as prediction, Prediction, Predictor};
+use baseball::predictors::{self, BradleyTerryPredictor, CountDistribution, CountPredictor, EloPredictor, RegressorKind, RegressorPredictor, SimulationPredictor, StrengthPredictor};
+use baseball::ratings::Elo;
+use baseball::run_expectancy::RunExpectancy;
+use baseball::simulation::GameSimulator;
 
 // all mlb team ids
 const TEAM_IDS: [u8; 30] = [108, 109, 110, 111, 112, 113, 114, 115, 116, 117, 118, 119, 120, 121, 133, 134, 135, 136, 137, 138, 139, 140, 141, 142, 143, 144, 145, 146, 147, 158];
 
+/// Parse a command's argument, or print the command's usage and exit if it's missing or invalid.
+fn argument<T: std::str::FromStr>(args: &[String], index: usize, usage: &str) -> T {
+    match args.get(index).map(|arg| arg.parse::<T>()) {
+        Some(Ok(value)) => value,
+        _ => {
+            eprintln!("usage: {}", usage);
+            std::process::exit(1);
+        },
+    }
+}
+
+/// Parse an optional argument of a command, which is `default` if it's missing.
+fn optional_argument<T: std::str::FromStr>(args: &[String], index: usize, default: T, usage: &str) -> T {
+    match args.get(index) {
+        Some(_) => argument(args, index, usage),
+        None => default,
+    }
+}
+
+/// The value of a result, or print its error and exit.
+fn or_exit<T>(result: Result<T, String>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    })
+}
+
 /// Remove the completed team from the list of teams to be processed in the given season.
 fn save_progress(season: u16, completed_team_id: u8) {
     let mut progress = serde_json::from_str::<serde_json::Value>(std::fs::read_to_string("data/progress.json").unwrap_or("{}".to_string()).as_str()).unwrap();
 
-    if progress.get(&season.to_string()).is_none() {
+    if progress.get(season.to_string()).is_none() {
         progress[season.to_string()] = serde_json::Value::Array(TEAM_IDS.iter().map(|id| serde_json::Value::Number(serde_json::Number::from(*id))).collect());
     }
 
-    let progress_season = progress.get_mut(&season.to_string()).unwrap().as_array_mut().unwrap();
+    let progress_season = progress.get_mut(season.to_string()).unwrap().as_array_mut().unwrap();
     progress_season.retain(|id| id.as_u64().unwrap() != completed_team_id as u64);
 
     std::fs::write("data/progress.json", serde_json::to_string_pretty(&progress).unwrap()).unwrap();
 }
 
+/// Parse the `--blend <games>` and `--splits` options of the commands that fetch team stats.
+fn fetch_options(args: &[String]) -> model::FetchOptions {
+    let mut options = model::FetchOptions::default();
+    if let Some(i) = args.iter().position(|arg| arg == "--blend") {
+        match args.get(i + 1).and_then(|games| games.parse::<f32>().ok()) {
+            Some(games) => options.prior_season_games = Some(games),
+            None => {
+                eprintln!("--blend needs the number of games to weight the prior season as");
+                std::process::exit(1);
+            },
+        }
+    }
+    options.splits = args.iter().any(|arg| arg == "--splits");
+
+    options
+}
+
+/// Print one row of an evaluation's metrics (home and away for the runs ones), with `-` for those it doesn't have.
+fn print_evaluation(label: &str, evaluation: &evaluation::Evaluation) {
+    let metric = |value: Option<f64>| value.map_or("-".to_string(), |value| format!("{:.3}", value));
+    let runs = |value: fn(&evaluation::RunMetrics) -> f64| {
+        format!("{:>6} {:>6}", metric(evaluation.home_runs.as_ref().map(value)), metric(evaluation.away_runs.as_ref().map(value)))
+    };
+    println!(
+        "{:<8} {:>6}  {}  {}  {}  {}  {:>6} {:>6} {:>6}  {:>6}",
+        label,
+        evaluation.predictions,
+        runs(|runs| runs.rmse),
+        runs(|runs| runs.mae),
+        runs(|runs| runs.r2),
+        runs(|runs| runs.poisson_deviance),
+        metric(evaluation.win.as_ref().map(|win| win.log_loss)),
+        metric(evaluation.win.as_ref().map(|win| win.brier_score)),
+        metric(evaluation.win.as_ref().map(|win| win.accuracy)),
+        metric(evaluation.total_runs_rps),
+    );
+}
+
+/// Print the probabilities of the markets a prediction prices at the default lines, if it has a score distribution.
+fn print_markets(prediction: &Prediction) {
+    let (Some(home_favorite), Some(away_favorite)) = (prediction.run_line(prediction::RUN_LINE), prediction.run_line(-prediction::RUN_LINE)) else {
+        return;
+    };
+
+    let overs = |lines: &[f64], market: &dyn Fn(f64) -> Option<prediction::OverUnder>| -> Option<String> {
+        let overs = lines.iter().map(|line| market(*line).map(|over_under| format!("{} {:.3}", line, over_under.over)));
+        overs.collect::<Option<Vec<String>>>().map(|overs| overs.join(", "))
+    };
+    let mut markets = vec![
+        format!("over {}", overs(&prediction::DEFAULT_TOTAL_LINES, &|line| prediction.total_runs(line)).unwrap()),
+        format!("home -{} {:.3}, away +{} {:.3}", prediction::RUN_LINE, home_favorite.home, prediction::RUN_LINE, home_favorite.away),
+        format!("home +{} {:.3}, away -{} {:.3}", prediction::RUN_LINE, away_favorite.home, prediction::RUN_LINE, away_favorite.away),
+    ];
+    if let Some(first_five) = overs(&prediction::DEFAULT_FIRST_FIVE_LINES, &|line| prediction.first_five_total_runs(line)) {
+        markets.push(format!("first five over {}", first_five));
+    }
+    println!("    {}", markets.join(", "));
+}
+
 #[tokio::main]
 async fn main() {
-    let season = env::args().nth(1).unwrap().parse::<u16>().unwrap();
+    let args: Vec<String> = env::args().collect();
+    if args[1] == "derive" {
+        history::derive_features();
+        return;
+    }
+    if args[1] == "refetch" {
+        let season: u16 = argument(&args, 2, "refetch <season> [--blend <games>] [--splits]");
+        let options = fetch_options(&args);
+        let mut games: Vec<model::Game> = history::load_games()
+            .into_iter()
+            .filter(|game| game.setting.date.year == season && !game.has_pregame_stats())
+            .collect();
+        println!("Re-fetching the team stats of {} games of {} stored with stats through their own date", games.len(), season);
+
+        let progress_style = ProgressStyle::default_bar().template("{wide_bar} {pos}/{len} | elapsed: {elapsed_precise}, eta: {eta_precise}").unwrap();
+        for game in games.iter_mut().progress_with_style(progress_style) {
+            if let Err(e) = game.refetch_team_stats(&options).await.and_then(|_| game.save()) {
+                eprintln!("{} {} @ {}: {}", game.setting.date, game.away_team.team_id, game.home_team.team_id, e);
+            }
+        }
+        println!("Run `cargo run derive` to recompute the derived features");
+        return;
+    }
+    if args[1] == "run-expectancy" {
+        let season: u16 = argument(&args, 2, "run-expectancy <season>");
+        let games: Vec<model::Game> = history::load_games().into_iter().filter(|game| game.setting.date.year == season).collect();
+        let run_expectancies = RunExpectancy::by_season(&games);
+        let Some(run_expectancy) = run_expectancies.get(&season) else {
+            eprintln!("no plays for season {}", season);
+            std::process::exit(1);
+        };
+
+        println!("bases    0 outs  1 out   2 outs");
+        for bases in 0..8 {
+            let runner = |base: u8, label: char| if bases & base != 0 { label } else { '-' };
+            print!("{}{}{}   ", runner(1, '1'), runner(2, '2'), runner(4, '3'));
+            for outs in 0..3 {
+                print!("  {:.3} ", run_expectancy.get(baseball::plays::BaseOutState { bases, outs }));
+            }
+            println!();
+        }
+        return;
+    }
+    if args[1] == "elo" {
+        let season: u16 = argument(&args, 2, "elo <season>");
+
+        // predict each game of the season from the games before it
+        let mut elo = Elo::default();
+        let mut log_loss = 0.0;
+        let mut correct = 0;
+        let mut games = 0;
+        for game in history::load_games().iter().filter(|game| game.setting.date.year <= season) {
+            elo.start_game(game);
+            if game.setting.date.year == season && game.result.home_team_score != game.result.away_team_score {
+                let probability = elo.home_win_probability(game.home_team.team_id, game.away_team.team_id);
+                let home_won = game.result.home_team_score > game.result.away_team_score;
+                log_loss -= if home_won { probability.ln() } else { (1.0 - probability).ln() };
+                correct += ((probability > 0.5) == home_won) as u32;
+                games += 1;
+            }
+            elo.update(game);
+        }
+        println!("{} games, log loss {:.4}, accuracy {:.3}", games, log_loss / games as f64, correct as f64 / games as f64);
+
+        let mut teams = TEAM_IDS.to_vec();
+        teams.sort_by(|a, b| elo.rating(*b).total_cmp(&elo.rating(*a)));
+        for team_id in teams {
+            println!("{}: {:.0}", team_id, elo.rating(team_id));
+        }
+        return;
+    }
+    if args[1] == "ratings" {
+        let from: model::Date = argument(&args, 2, "ratings <from> <to>");
+        let to: model::Date = argument(&args, 3, "ratings <from> <to>");
+
+        let games = history::load_games();
+        let games: Vec<&model::Game> = games.iter().filter(|game| from <= game.setting.date && game.setting.date <= to).collect();
+        let (Some(massey), Some(bradley_terry)) = (Massey::fit(games.iter().copied()), BradleyTerry::fit(games.iter().copied())) else {
+            eprintln!("failed to fit the ratings to the {} games from {} to {}", games.len(), from, to);
+            std::process::exit(1);
+        };
+        println!(
+            "{} games, league runs {:.3}, home advantage {:.3} runs, {:.3} log odds",
+            games.len(),
+            massey.league_runs,
+            massey.home_advantage,
+            bradley_terry.home_advantage,
+        );
+
+        let mut teams = TEAM_IDS.to_vec();
+        teams.sort_by(|a, b| bradley_terry.strength(*b).total_cmp(&bradley_terry.strength(*a)));
+        println!("team  offense  defense  strength");
+        for team_id in teams {
+            let (offense, defense) = massey.ratings(team_id);
+            println!("{}   {:+.3}   {:+.3}   {:+.3}", team_id, offense, defense, bradley_terry.strength(team_id));
+        }
+        return;
+    }
+    if args[1] == "simulate" {
+        let usage = "simulate <date> [simulations] [seed]";
+        let date: model::Date = argument(&args, 2, usage);
+        let simulations: u32 = optional_argument(&args, 3, 10000, usage);
+        let seed: u64 = optional_argument(&args, 4, 0, usage);
+
+        let games = history::load_games();
+        let league = EventRates::league(games.iter().filter(|game| game.setting.date.year == date.year && game.setting.date < date));
+        for game in games.iter().filter(|game| game.setting.date == date) {
+            let result = GameSimulator::for_game(game, &league).simulate(simulations, seed);
+            let quantiles: Vec<String> = result.total_runs_quantiles.iter().map(|quantile| format!("{}", quantile.runs)).collect();
+            println!(
+                "{} @ {}: {:.2}-{:.2}, home win {:.3}, total runs quantiles [{}], extra innings {:.3}",
+                game.away_team.team_id,
+                game.home_team.team_id,
+                result.expected_away_runs,
+                result.expected_home_runs,
+                result.home_win_probability,
+                quantiles.join(", "),
+                result.extra_innings_frequency,
+            );
+            print_markets(&Prediction::from_simulation(&result));
+        }
+        return;
+    }
+    if args[1] == "scores" {
+        let date: model::Date = argument(&args, 2, "scores <date>");
+
+        let games = history::load_games();
+        // on opening day there are no earlier games in the season to fit to
+        let count_model = match CountModel::fit(games.iter().filter(|game| game.setting.date.year == date.year && game.setting.date < date)) {
+            Ok(count_model) => count_model,
+            Err(e) => {
+                eprintln!("failed to fit the count model to the games of {} before {}: {}", date.year, date, e);
+                std::process::exit(1);
+            }
+        };
+        println!(
+            "baseline {:.3}, home advantage {:.3}, dispersion {}, covariance {:.3}",
+            count_model.baseline,
+            count_model.home_advantage,
+            count_model.dispersion.map_or("none".to_string(), |dispersion| format!("{:.2}", dispersion)),
+            count_model.covariance,
+        );
+        for game in games.iter().filter(|game| game.setting.date == date) {
+            let (home_runs, away_runs) = count_model.expected_runs(game);
+            let negative_binomial = Prediction::from_score_probabilities(count_model.negative_binomial_grid(game));
+            let bivariate_poisson = Prediction::from_score_probabilities(count_model.bivariate_poisson_grid(game));
+            println!("{} @ {}: {:.2}-{:.2}", game.away_team.team_id, game.home_team.team_id, away_runs, home_runs);
+            for (name, prediction) in [("negative binomial", negative_binomial), ("bivariate poisson", bivariate_poisson)] {
+                println!("  {}: home win {:.3}", name, prediction.home_win_probability.unwrap());
+                print_markets(&prediction);
+            }
+        }
+        return;
+    }
+    if args[1] == "train" {
+        let usage = "train <ridge|poisson|boost|boost-poisson> [alpha]";
+        let kind: String = argument(&args, 2, usage);
+        let kind = kind.as_str();
+        let alpha: f64 = optional_argument(&args, 3, 1.0, usage);
+        if !["ridge", "poisson", "boost", "boost-poisson"].contains(&kind) {
+            eprintln!("usage: {}", usage);
+            std::process::exit(1);
+        }
+
+        let dataset = or_exit(Dataset::load("data/data.csv"));
+        let (train, test) = dataset.split(0.2, 42);
+        let path = format!("{}/{}.json", models::MODELS_DIR, kind);
+        let model: Box<dyn Regressor> = match kind {
+            "ridge" | "poisson" => {
+                let model = or_exit(if kind == "ridge" { LinearModel::fit_ridge(&train, alpha) } else { LinearModel::fit_poisson(&train, alpha) });
+                or_exit(model.save(&path));
+                Box::new(model)
+            },
+            _ => {
+                let params = BoostingParams {
+                    loss: if kind == "boost" { Loss::SquaredError } else { Loss::Poisson },
+                    ..BoostingParams::default()
+                };
+                // early stopping watches a validation set held out of the training set, not the test set
+                let (train, validation) = train.split(0.2, 42);
+                let model = or_exit(GradientBoosting::fit(&train, &validation, &params));
+                or_exit(model.save(&path));
+                Box::new(model)
+            },
+        };
+
+        let predictions: Vec<Vec<f64>> = test.features.iter().map(|row| model.predict(row)).collect();
+        for (i, target) in test.target_names.iter().enumerate() {
+            let predicted: Vec<f64> = predictions.iter().map(|prediction| prediction[i]).collect();
+            let actual = test.target(i);
+            println!(
+                "{}: rmse {:.4}, r2 {:.4}, mae {:.4}",
+                target,
+                evaluation::root_mean_squared_error(&actual, &predicted),
+                evaluation::r2_score(&actual, &predicted),
+                evaluation::mean_absolute_error(&actual, &predicted),
+            );
+        }
+        return;
+    }
+    if args[1] == "parity" {
+        let mut identical = true;
+        for name in ["xgboost", "nn"] {
+            let model_path = format!("{}/{}.{}", models::MODELS_DIR, name, if name == "nn" { "onnx" } else { "json" });
+            let scaler_path = format!("{}/{}_scaler.json", models::MODELS_DIR, name);
+            if !std::path::Path::new(&model_path).exists() {
+                println!("{}: not exported, skipping", name);
+                continue;
+            }
+            let model: Box<dyn Regressor> = match name {
+                "xgboost" => Box::new(or_exit(XGBoostModel::load(&model_path, &scaler_path))),
+                _ => Box::new(or_exit(OnnxModel::load(&model_path, &scaler_path))),
+            };
+
+            let fixture = or_exit(parity::Fixture::load(&format!("{}/{}_fixture.json", models::MODELS_DIR, name)));
+            let difference = or_exit(parity::max_difference(model.as_ref(), &fixture));
+            println!("{}: {} rows, max difference {:e}", name, fixture.rows.len(), difference);
+            identical &= difference <= parity::TOLERANCE;
+        }
+
+        if !identical {
+            println!("predictions differ from python by more than {:e}", parity::TOLERANCE);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args[1] == "backtest" {
+        let usage = "backtest <elo|strength|bradley-terry|negative-binomial|bivariate-poisson|simulation|ridge|poisson|boost|boost-poisson> <from> <to> [--weekly]";
+        let kind: String = argument(&args, 2, usage);
+        let kind = kind.as_str();
+        let from: model::Date = argument(&args, 3, usage);
+        let to: model::Date = argument(&args, 4, usage);
+        let step = if args.iter().any(|arg| arg == "--weekly") { backtest::Step::Weekly } else { backtest::Step::Daily };
+
+        let regressor = |kind: RegressorKind| {
+            // the regressions use the same features as the models trained from data/data.csv
+            let feature_names = or_exit(Dataset::load("data/data.csv")).feature_names;
+            RegressorPredictor::new(kind, &feature_names)
+        };
+        let mut predictor: Box<dyn Predictor> = match kind {
+            "elo" => Box::new(EloPredictor::default()),
+            "strength" => Box::new(StrengthPredictor),
+            "bradley-terry" => Box::new(BradleyTerryPredictor::new(predictors::DEFAULT_WINDOW)),
+            "negative-binomial" => Box::new(CountPredictor::new(CountDistribution::NegativeBinomial, predictors::DEFAULT_WINDOW)),
+            "bivariate-poisson" => Box::new(CountPredictor::new(CountDistribution::BivariatePoisson, predictors::DEFAULT_WINDOW)),
+            "simulation" => Box::new(SimulationPredictor::new(1000, 0, predictors::DEFAULT_WINDOW)),
+            "ridge" => Box::new(regressor(RegressorKind::Ridge { alpha: 1.0 })),
+            "poisson" => Box::new(regressor(RegressorKind::Poisson { alpha: 1.0 })),
+            "boost" | "boost-poisson" => Box::new(regressor(RegressorKind::Boosting(BoostingParams {
+                loss: if kind == "boost" { Loss::SquaredError } else { Loss::Poisson },
+                ..BoostingParams::default()
+            }))),
+            _ => {
+                eprintln!("usage: {}", usage);
+                std::process::exit(1);
+            },
+        };
+
+        let mut games = history::load_games();
+        if matches!(kind, "simulation" | "ridge" | "poisson" | "boost" | "boost-poisson") {
+            // these use the fetched team stats, which for games stored with the old cutoff include the game itself
+            games.retain(|game| game.has_pregame_stats());
+        }
+        let backtest = backtest::walk_forward(predictor.as_mut(), &games, from, to, step);
+        let path = format!("{}/{}_{}_{}.json", backtest::BACKTESTS_DIR, kind, from, to);
+        or_exit(backtest.save(&path));
+        println!("{} predictions by {} saved to {}", backtest.records.len(), predictor.version(), path);
+        return;
+    }
+    if args[1] == "evaluate" {
+        let path: String = argument(&args, 2, "evaluate <backtest file> [--by season|month|team]");
+        let grouping = args.iter().position(|arg| arg == "--by").map(|i| match args.get(i + 1) {
+            Some(grouping) => or_exit(grouping.parse::<evaluation::Grouping>()),
+            None => {
+                eprintln!("--by needs a grouping: season, month or team");
+                std::process::exit(1);
+            },
+        });
+        let backtest = or_exit(backtest::Backtest::load(&path));
+
+        let overall = evaluation::Evaluation::new(&backtest.records);
+
+        // the runs metrics are home then away
+        println!(
+            "{:<8} {:>6}  {:>13}  {:>13}  {:>13}  {:>13}  {:>6} {:>6} {:>6}  {:>6}",
+            "group", "games", "rmse", "mae", "r2", "deviance", "loss", "brier", "acc", "rps",
+        );
+        print_evaluation("all", &overall);
+        if let Some(grouping) = grouping {
+            for (key, group) in evaluation::evaluate_by(&backtest.records, grouping) {
+                print_evaluation(&key.to_string(), &group);
+            }
+        }
+
+        if let Some(win) = overall.win {
+            println!("\nhome win probability  games  mean predicted  observed");
+            for bin in win.calibration {
+                println!("{:.1}-{:.1}               {:>5}  {:.3}           {:.3}", bin.lower, bin.upper, bin.count, bin.mean_probability, bin.observed_frequency);
+            }
+        }
+        return;
+    }
+    if args[1] == "markets" {
+        let usage = "markets <backtest file> [--lines <lines>] [--first-five-lines <lines>]";
+        let path: String = argument(&args, 2, usage);
+        let path = path.as_str();
+        let lines = |flag: &str, default: &[f64]| match args.iter().position(|arg| arg == flag) {
+            Some(i) => match args.get(i + 1).map(|lines| lines.split(',').map(|line| line.parse::<f64>()).collect::<Result<Vec<f64>, _>>()) {
+                Some(Ok(lines)) => lines,
+                _ => {
+                    eprintln!("{} needs comma-separated lines, e.g. 7.5,8.5", flag);
+                    std::process::exit(1);
+                },
+            },
+            None => default.to_vec(),
+        };
+
+        let total_lines = lines("--lines", &prediction::DEFAULT_TOTAL_LINES);
+        let first_five_lines = lines("--first-five-lines", &prediction::DEFAULT_FIRST_FIVE_LINES);
+
+        let backtest = or_exit(backtest::Backtest::load(path));
+        let markets_path = format!("{}_markets.csv", path.trim_end_matches(".json"));
+        or_exit(backtest.save_markets(&markets_path, &total_lines, &first_five_lines));
+        println!("markets of {} predictions saved to {}", backtest.records.len(), markets_path);
+        return;
+    }
+    if args[1] == "plays" {
+        let season: u16 = argument(&args, 2, "plays <season>");
+        let games: Vec<model::Game> = history::load_games().into_iter().filter(|game| game.setting.date.year == season).collect();
+        plays::ingest(&games, false).await;
+        return;
+    }
+
+    let season = args[1].parse::<u16>().unwrap();
+    let options = fetch_options(&args);
 
     /*for id in team_ids.iter().progress_with_style(ProgressStyle::default_bar().template("{wide_bar} {pos}/{len} | elapsed: {elapsed_precise}, eta: {eta_precise}").unwrap()) {
         let games = model::Game::get_all_by_team_in_season(*id, season, false).await;
@@ -32,7 +463,7 @@ async fn main() {
     }*/
 
     let progress = serde_json::from_str::<serde_json::Value>(std::fs::read_to_string("data/progress.json").unwrap_or("{}".to_string()).as_str()).unwrap();
-    let progress_season = match progress.get(&season.to_string()) {
+    let progress_season = match progress.get(season.to_string()) {
         Some(progress_season) => progress_season.as_array().unwrap().iter().map(|id| id.as_u64().unwrap() as u8).collect(),
         None => TEAM_IDS.to_vec(),
     };
@@ -40,7 +471,7 @@ async fn main() {
 
     let progress_style = ProgressStyle::default_bar().template("{wide_bar} {pos}/{len} | elapsed: {elapsed_precise}, eta: {eta_precise}").unwrap();
     for id in progress_season.iter().progress_with_style(progress_style) {
-        let games = model::Game::get_all_by_team_in_season(*id, season, false).await;
+        let games = model::Game::get_all_by_team_in_season(*id, season, false, &options).await;
         for game in games {
             let _ = game.save();
         }
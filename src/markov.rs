@@ -0,0 +1,225 @@
+use crate::event_rates::{Event, EventRates, EVENTS};
+use crate::model::Team;
+use crate::plays::BaseOutState;
+
+/// The share of outs in play with a runner on first and fewer than two outs that are double plays.
+const DOUBLE_PLAY_RATE: f64 = 0.12;
+
+/// The share of other outs in play with fewer than two outs on which every runner advances a base.
+const ADVANCE_ON_OUT_RATE: f64 = 0.4;
+
+/// The most runs tracked in a distribution; the last bucket holds this many runs or more.
+pub const MAX_RUNS: usize = 30;
+
+/// The number of plate appearances after which a half-inning still in progress is ignored.
+const MAX_PLATE_APPEARANCES: usize = 40;
+
+/// One possible result of an event in a base-out state.
+#[derive(Debug, Clone, Copy)]
+pub struct Transition {
+    pub probability: f64,
+    pub state: BaseOutState,
+    pub runs: u8,
+}
+
+/// Advance every runner by `bases` bases, returning the new bases and the runs scored.
+fn advance_runners(occupied: u8, bases: u8) -> (u8, u8) {
+    let runs = (0..3).filter(|base| occupied & (1 << base) != 0 && base + bases >= 3).count() as u8;
+    ((occupied << bases) & 7, runs)
+}
+
+/// The possible results of `event` in `state`, using simple baserunning rules: singles and doubles score runners from
+/// second, singles move a runner from first to second and doubles to third, walks only force runners along, and
+/// outs in play are sometimes double plays or productive outs.
+pub fn transitions(state: BaseOutState, event: Event) -> Vec<Transition> {
+    let BaseOutState { bases, outs } = state;
+    let transition = |probability: f64, bases: u8, outs: u8, runs: u8| Transition {
+        probability,
+        state: BaseOutState { bases, outs },
+        // runs don't count on a play that makes the third out
+        runs: if outs < 3 { runs } else { 0 },
+    };
+
+    match event {
+        Event::Walk => {
+            // runners only move when forced
+            let (bases, runs) = if bases & 1 == 0 {
+                (bases | 1, 0)
+            } else if bases & 2 == 0 {
+                (bases | 3, 0)
+            } else if bases & 4 == 0 {
+                (7, 0)
+            } else {
+                (7, 1)
+            };
+            vec![transition(1.0, bases, outs, runs)]
+        },
+        Event::Single => {
+            let runs = (bases & 2 != 0) as u8 + (bases & 4 != 0) as u8;
+            let bases = if bases & 1 != 0 { 3 } else { 1 };
+            vec![transition(1.0, bases, outs, runs)]
+        },
+        Event::Double => {
+            let runs = (bases & 2 != 0) as u8 + (bases & 4 != 0) as u8;
+            let bases = if bases & 1 != 0 { 6 } else { 2 };
+            vec![transition(1.0, bases, outs, runs)]
+        },
+        Event::Triple => {
+            let (_, runs) = advance_runners(bases, 3);
+            vec![transition(1.0, 4, outs, runs)]
+        },
+        Event::HomeRun => {
+            let (_, runs) = advance_runners(bases, 3);
+            vec![transition(1.0, 0, outs, runs + 1)]
+        },
+        Event::Strikeout => vec![transition(1.0, bases, outs + 1, 0)],
+        Event::OutInPlay => {
+            if outs >= 2 {
+                return vec![transition(1.0, bases, outs + 1, 0)];
+            }
+
+            let mut results = Vec::new();
+            let mut remaining = 1.0;
+            if bases & 1 != 0 {
+                let (advanced, runs) = advance_runners(bases & !1, 1);
+                results.push(transition(DOUBLE_PLAY_RATE, advanced, outs + 2, runs));
+                remaining -= DOUBLE_PLAY_RATE;
+            }
+            let (advanced, runs) = advance_runners(bases, 1);
+            results.push(transition(remaining * ADVANCE_ON_OUT_RATE, advanced, outs + 1, runs));
+            results.push(transition(remaining * (1.0 - ADVANCE_ON_OUT_RATE), bases, outs + 1, 0));
+            results
+        },
+    }
+}
+
+/// A probability distribution over a number of runs.
+#[derive(Debug, Clone)]
+pub struct RunsDistribution {
+    /// The probability of each number of runs, from 0 to [`MAX_RUNS`].
+    pub probabilities: Vec<f64>,
+}
+
+impl RunsDistribution {
+    pub fn mean(&self) -> f64 {
+        self.probabilities.iter().enumerate().map(|(runs, probability)| runs as f64 * probability).sum()
+    }
+
+    /// The distribution of the sum of independent draws from this distribution and `other`.
+    pub fn add(&self, other: &RunsDistribution) -> Self {
+        let mut probabilities = vec![0.0; MAX_RUNS + 1];
+        for (runs, probability) in self.probabilities.iter().enumerate() {
+            for (other_runs, other_probability) in other.probabilities.iter().enumerate() {
+                probabilities[(runs + other_runs).min(MAX_RUNS)] += probability * other_probability;
+            }
+        }
+
+        RunsDistribution { probabilities }
+    }
+}
+
+/// A Markov chain over the 24 base-out states of a half-inning, with fixed event rates for every plate appearance.
+pub struct HalfInningSimulator {
+    pub rates: EventRates,
+}
+
+impl HalfInningSimulator {
+    pub fn new(rates: EventRates) -> Self {
+        HalfInningSimulator { rates }
+    }
+
+    /// The distribution of runs scored from `start` until the end of the half-inning.
+    pub fn runs_from(&self, start: BaseOutState) -> RunsDistribution {
+        let mut result = vec![0.0; MAX_RUNS + 1];
+        // probability of being in each of the 24 states having scored each number of runs
+        let mut current = vec![[0.0; MAX_RUNS + 1]; 24];
+        if let Some(index) = start.index() {
+            current[index][0] = 1.0;
+        } else {
+            result[0] = 1.0;
+        }
+
+        for _ in 0..MAX_PLATE_APPEARANCES {
+            let mut next = vec![[0.0; MAX_RUNS + 1]; 24];
+            for (index, runs_probabilities) in current.iter().enumerate() {
+                let state = BaseOutState {
+                    bases: (index % 8) as u8,
+                    outs: (index / 8) as u8,
+                };
+                for event in EVENTS {
+                    let event_probability = self.rates.probability(event);
+                    if event_probability == 0.0 {
+                        continue;
+                    }
+                    for transition in transitions(state, event) {
+                        let probability = event_probability * transition.probability;
+                        for (runs, runs_probability) in runs_probabilities.iter().enumerate() {
+                            if *runs_probability == 0.0 {
+                                continue;
+                            }
+                            let total_runs = (runs + transition.runs as usize).min(MAX_RUNS);
+                            match transition.state.index() {
+                                Some(next_index) => next[next_index][total_runs] += runs_probability * probability,
+                                None => result[total_runs] += runs_probability * probability,
+                            }
+                        }
+                    }
+                }
+            }
+            current = next;
+        }
+
+        RunsDistribution { probabilities: result }
+    }
+
+    /// The expected runs from `start` until the end of the half-inning.
+    pub fn expected_runs_from(&self, start: BaseOutState) -> f64 {
+        self.runs_from(start).mean()
+    }
+
+    /// The distribution of runs scored over `innings` full innings.
+    pub fn runs_over(&self, innings: u8) -> RunsDistribution {
+        let half_inning = self.runs_from(BaseOutState { bases: 0, outs: 0 });
+
+        let mut total = RunsDistribution { probabilities: vec![0.0; MAX_RUNS + 1] };
+        total.probabilities[0] = 1.0;
+        for _ in 0..innings {
+            total = total.add(&half_inning);
+        }
+        total
+    }
+}
+
+/// The distribution of runs the batting team scores against the pitching team over `innings` innings, using the two
+/// teams' snapshot stats combined by log5 against the league's rates.
+pub fn matchup_runs(batting: &Team, pitching: &Team, league: &EventRates, innings: u8) -> RunsDistribution {
+    let rates = EventRates::for_game(&batting.batting_stats, &pitching.pitching_stats, league);
+
+    HalfInningSimulator::new(rates).runs_over(innings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(distribution: &RunsDistribution) -> f64 {
+        distribution.probabilities.iter().sum()
+    }
+
+    #[test]
+    fn half_inning_distributions_sum_to_one() {
+        let simulator = HalfInningSimulator::new(EventRates::LEAGUE_AVERAGE);
+        for index in 0..24 {
+            let start = BaseOutState { bases: (index % 8) as u8, outs: (index / 8) as u8 };
+            let distribution = simulator.runs_from(start);
+            assert!((total(&distribution) - 1.0).abs() < 1e-9, "{:?} sums to {}", start, total(&distribution));
+        }
+    }
+
+    #[test]
+    fn game_distribution_sums_to_one() {
+        let distribution = HalfInningSimulator::new(EventRates::LEAGUE_AVERAGE).runs_over(9);
+        assert!((total(&distribution) - 1.0).abs() < 1e-9, "sums to {}", total(&distribution));
+        assert!(distribution.mean() > 3.0 && distribution.mean() < 6.0, "mean {}", distribution.mean());
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use crate::model::Game;
+use crate::models::linalg::cholesky_solve;
+use crate::ratings::Ratings;
+
+/// The ridge penalty on every rating: the variance of a team's runs in a game over the variance of teams' ratings,
+/// which makes the solution the posterior mean under a normal prior.
+const PENALTY: f64 = 38.0;
+
+/// Opponent-adjusted offense and defense ratings: the least-squares fit of each team's runs in a game as the league
+/// average, plus a home-field advantage, plus its offense, plus the opponent's defense (runs allowed above average).
+#[derive(Debug, Clone)]
+pub struct Massey {
+    pub league_runs: f64,
+    pub home_advantage: f64,
+    pub offense: HashMap<u8, f64>,
+    pub defense: HashMap<u8, f64>,
+}
+
+/// The normal equations of the Massey fit, to which games can be added one at a time.
+///
+/// The unknowns are the league average, the home-field advantage, then each team's offense and defense.
+pub struct MasseySystem {
+    indices: HashMap<u8, usize>,
+    xtx: Vec<f64>,
+    xty: Vec<f64>,
+    games: usize,
+}
+
+impl MasseySystem {
+    pub fn new(team_ids: impl IntoIterator<Item = u8>) -> Self {
+        let mut indices = HashMap::new();
+        for team_id in team_ids {
+            let index = 2 + 2 * indices.len();
+            indices.entry(team_id).or_insert(index);
+        }
+
+        let size = 2 + 2 * indices.len();
+        let mut xtx = vec![0.0; size * size];
+        for i in 2..size {
+            xtx[i * size + i] = PENALTY;
+        }
+
+        MasseySystem {
+            indices,
+            xtx,
+            xty: vec![0.0; size],
+            games: 0,
+        }
+    }
+
+    fn add_row(&mut self, columns: &[usize], runs: f64) {
+        let size = self.xty.len();
+        for &i in columns {
+            self.xty[i] += runs;
+            for &j in columns {
+                self.xtx[i * size + j] += 1.0;
+            }
+        }
+    }
+
+    pub fn add(&mut self, game: &Game) {
+        let home = self.indices[&game.home_team.team_id];
+        let away = self.indices[&game.away_team.team_id];
+
+        self.add_row(&[0, 1, home, away + 1], game.result.home_team_score as f64);
+        self.add_row(&[0, away, home + 1], game.result.away_team_score as f64);
+        self.games += 1;
+    }
+
+    /// Solve for the ratings, if any games have been added.
+    pub fn solve(&self) -> Option<Massey> {
+        if self.games == 0 {
+            return None;
+        }
+
+        let solution = cholesky_solve(&self.xtx, &self.xty).ok()?;
+        Some(Massey {
+            league_runs: solution[0],
+            home_advantage: solution[1],
+            offense: self.indices.iter().map(|(team_id, index)| (*team_id, solution[*index])).collect(),
+            defense: self.indices.iter().map(|(team_id, index)| (*team_id, solution[*index + 1])).collect(),
+        })
+    }
+}
+
+impl Massey {
+    pub fn fit<'a>(games: impl IntoIterator<Item = &'a Game>) -> Option<Self> {
+        let games: Vec<&Game> = games.into_iter().collect();
+
+        let mut system = MasseySystem::new(games.iter().flat_map(|game| [game.home_team.team_id, game.away_team.team_id]));
+        for game in games {
+            system.add(game);
+        }
+
+        system.solve()
+    }
+
+    /// A team's offense and defense, or average ones if it hasn't been seen.
+    pub fn ratings(&self, team_id: u8) -> (f64, f64) {
+        (
+            self.offense.get(&team_id).copied().unwrap_or(0.0),
+            self.defense.get(&team_id).copied().unwrap_or(0.0),
+        )
+    }
+
+    /// The home and away teams' expected runs in a game.
+    pub fn expected_runs(&self, game: &Game) -> (f64, f64) {
+        let (home_offense, home_defense) = self.ratings(game.home_team.team_id);
+        let (away_offense, away_defense) = self.ratings(game.away_team.team_id);
+
+        (
+            self.league_runs + self.home_advantage + home_offense + away_defense,
+            self.league_runs + away_offense + home_defense,
+        )
+    }
+}
+
+/// Set both teams' Massey ratings going into every game, from the season's games before its date.
+///
+/// `games` must be in chronological order.
+pub fn assign_massey(games: &mut [Game]) {
+    let team_ids: Vec<u8> = games.iter().flat_map(|game| [game.home_team.team_id, game.away_team.team_id]).collect();
+
+    let mut system = MasseySystem::new(team_ids.iter().copied());
+    let mut start = 0;
+    while start < games.len() {
+        let date = games[start].setting.date;
+        let end = start + games[start..].iter().take_while(|game| game.setting.date == date).count();
+        if start > 0 && games[start - 1].setting.date.year != date.year {
+            system = MasseySystem::new(team_ids.iter().copied());
+        }
+
+        let massey = system.solve();
+        for game in games[start..end].iter_mut() {
+            for team in [&mut game.home_team, &mut game.away_team] {
+                let ratings = team.ratings.get_or_insert_with(Ratings::default);
+                let team_ratings = massey.as_ref().map(|massey| massey.ratings(team.team_id));
+                ratings.massey_offense = team_ratings.map(|(offense, _)| offense as f32);
+                ratings.massey_defense = team_ratings.map(|(_, defense)| defense as f32);
+            }
+        }
+        for game in &games[start..end] {
+            system.add(game);
+        }
+
+        start = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn ratings_solve_the_normal_equations() {
+        let mut games = vec![
+            Game::with_score("2024-04-01", 1, 2, 5, 2),
+            Game::with_score("2024-04-01", 3, 4, 1, 7),
+            Game::with_score("2024-04-02", 2, 3, 3, 4),
+            Game::with_score("2024-04-02", 4, 1, 6, 6),
+            Game::with_score("2024-04-03", 1, 3, 9, 0),
+            Game::with_score("2024-04-04", 2, 4, 0, 0),
+        ];
+        assign_massey(&mut games);
+        let massey = Massey::fit(&games[..5]).unwrap();
+
+        // the derivative of the penalized squared error with respect to each unknown is zero
+        let mut league_residual = 0.0;
+        let mut home_residual = 0.0;
+        let mut offense_residuals: HashMap<u8, f64> = HashMap::new();
+        let mut defense_residuals: HashMap<u8, f64> = HashMap::new();
+        for game in &games[..5] {
+            let (home_runs, away_runs) = massey.expected_runs(game);
+            let home_residual_runs = game.result.home_team_score as f64 - home_runs;
+            let away_residual_runs = game.result.away_team_score as f64 - away_runs;
+
+            league_residual += home_residual_runs + away_residual_runs;
+            home_residual += home_residual_runs;
+            *offense_residuals.entry(game.home_team.team_id).or_default() += home_residual_runs;
+            *defense_residuals.entry(game.away_team.team_id).or_default() += home_residual_runs;
+            *offense_residuals.entry(game.away_team.team_id).or_default() += away_residual_runs;
+            *defense_residuals.entry(game.home_team.team_id).or_default() += away_residual_runs;
+        }
+        assert_close(league_residual, 0.0);
+        assert_close(home_residual, 0.0);
+        for team_id in 1..=4 {
+            let (offense, defense) = massey.ratings(team_id);
+            assert_close(offense_residuals[&team_id], PENALTY * offense);
+            assert_close(defense_residuals[&team_id], PENALTY * defense);
+        }
+
+        // which makes the offenses and defenses sum to zero
+        assert_close(massey.offense.values().sum(), 0.0);
+        assert_close(massey.defense.values().sum(), 0.0);
+
+        // the last game's ratings are fit to the games before its date
+        let ratings = games[5].home_team.ratings.as_ref().unwrap();
+        assert_eq!(ratings.massey_offense, Some(massey.ratings(2).0 as f32));
+        assert_eq!(ratings.massey_defense, Some(massey.ratings(2).1 as f32));
+    }
+}
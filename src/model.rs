@@ -1,6 +1,18 @@
 use indicatif::ProgressIterator;
 use reqwest::Response;
-use serde::{Serialize, Deserialize, Deserializer};
+use serde::{Serialize, Deserialize};
+
+use crate::bullpen::Bullpen;
+use crate::expected_wins::ExpectedWins;
+use crate::game_time::{DayNight, DoubleHeader, GameTime, LocalTime, Weather};
+use crate::head_to_head::{HeadToHead, SeriesContext};
+use crate::linescore::Linescore;
+use crate::pitcher::StartingPitcher;
+use crate::ratings::Ratings;
+use crate::rest::Rest;
+use crate::splits::Splits;
+use crate::standings::{Standing, Standings};
+use crate::venue::Venue;
 
 async fn send_request(url: &str, max_retries: usize) -> Result<Response, String> {
     for _ in 0..max_retries {
@@ -17,264 +29,455 @@ async fn send_request(url: &str, max_retries: usize) -> Result<Response, String>
     Err("Request failed".to_string())
 }
 
-fn parse_mlb_percentage(value: &serde_json::Value) -> Result<f32, String> {
-    let Some(value) = value.as_str() else {
-        // println!("{:?}", value);
-        return Err("Value is not a string".to_string());
+/// Parse a rate stat, which the MLB API sends as a string (e.g. `".247"`).
+///
+/// Rates that are undefined for the sample (e.g. stolen base percentage with no attempts) are sent as null or as
+/// placeholders like `".---"` or `"-.--"`, and are returned as `None`.
+fn parse_mlb_percentage(value: &serde_json::Value) -> Option<f32> {
+    value.as_str()?.parse::<f32>().ok().filter(|value| value.is_finite())
+}
+
+/// Undo the wrapping of a count saved while counting stats were stored as `u8`, given an estimate of it from the rate
+/// stats (which weren't affected): the stored count plus the multiple of 256 that brings it closest to the estimate.
+fn unwrap_count(stored: u16, estimate: Option<f32>) -> u16 {
+    let Some(estimate) = estimate.filter(|estimate| estimate.is_finite()) else {
+        return stored;
     };
+    let wraps = ((estimate - stored as f32) / 256.0).round().max(0.0);
 
-    match value.parse::<f32>() {
-        Ok(value) => Ok(value),
-        Err(_) => Err("Failed to parse value".to_string()),
+    stored + wraps as u16 * 256
+}
+
+/// `numerator / denominator`, or `None` if the denominator is zero.
+pub(crate) fn ratio(numerator: f32, denominator: f32) -> Option<f32> {
+    if denominator == 0.0 {
+        None
+    } else {
+        Some(numerator / denominator)
     }
 }
 
-#[derive(Debug, Serialize)]
+pub(crate) async fn fetch_json(url: &str) -> Result<serde_json::Value, String> {
+    match send_request(url, 5).await {
+        Ok(response) => Ok(response.json::<serde_json::Value>().await.unwrap()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Stats that the MLB API reports as strings but which are counts rather than rates.
+const COUNTING_STRING_STATS: [&str; 2] = ["inningsPitched", "innings"];
+
+/// Convert an innings value in MLB notation (e.g. `"6.2"` for six and two thirds) to outs.
+fn innings_to_outs(value: &serde_json::Value) -> Option<f32> {
+    let innings = value.as_str()?;
+    let (whole, thirds) = innings.split_once('.').unwrap_or((innings, "0"));
+    Some(whole.parse::<f32>().ok()? * 3.0 + thirds.parse::<f32>().ok()?)
+}
+
+/// Convert an innings value in MLB notation that has already been parsed as a number (e.g. `6.2`) to outs.
+fn parsed_innings_to_outs(innings: f32) -> f32 {
+    let whole = innings.trunc();
+    whole * 3.0 + ((innings - whole) * 10.0).round()
+}
+
+fn outs_to_innings(outs: f32) -> String {
+    let outs = outs.round() as u64;
+    format!("{}.{}", outs / 3, outs % 3)
+}
+
+/// Blend a raw MLB stat object for the current window with one for the whole prior season.
+///
+/// The prior season counts as `prior_games` games: its counting stats are scaled down to that many games and
+/// added to the window's, and its rate stats are averaged with the window's, weighted by games played.
+/// A missing window (`Value::Null`) is treated as zero games.
+fn blend_stat_values(current: &serde_json::Value, prior: &serde_json::Value, prior_games: f32) -> serde_json::Value {
+    let Some(prior_fields) = prior.as_object() else {
+        return current.clone();
+    };
+    let prior_games_played = prior["gamesPlayed"].as_f64().unwrap_or(0.0) as f32;
+    if prior_games_played == 0.0 {
+        return current.clone();
+    }
+
+    let current_games_played = current["gamesPlayed"].as_f64().unwrap_or(0.0) as f32;
+    let scale = prior_games / prior_games_played;
+    let prior_weight = prior_games / (current_games_played + prior_games);
+
+    // every stat in either object, so that one missing from the prior season isn't dropped
+    let current_only = current.as_object().into_iter().flat_map(|fields| fields.keys()).filter(|key| !prior_fields.contains_key(*key));
+    let keys: Vec<&String> = prior_fields.keys().chain(current_only).collect();
+
+    let mut blended = serde_json::Map::new();
+    for key in keys {
+        let current_value = &current[key];
+        let prior_value = &prior[key];
+        let value = if prior_value.is_number() || current_value.is_number() {
+            let total = current_value.as_f64().unwrap_or(0.0) as f32 + prior_value.as_f64().unwrap_or(0.0) as f32 * scale;
+            serde_json::Value::from(total.round() as u64)
+        } else if COUNTING_STRING_STATS.contains(&key.as_str()) {
+            let outs = innings_to_outs(current_value).unwrap_or(0.0) + innings_to_outs(prior_value).unwrap_or(0.0) * scale;
+            serde_json::Value::from(outs_to_innings(outs))
+        } else if prior_value.is_string() || current_value.is_string() {
+            match (parse_mlb_percentage(current_value), parse_mlb_percentage(prior_value)) {
+                (Some(current_rate), Some(prior_rate)) => {
+                    let rate = current_rate * (1.0 - prior_weight) + prior_rate * prior_weight;
+                    serde_json::Value::from(format!("{:.3}", rate))
+                },
+                (Some(_), None) => current_value.clone(),
+                (None, Some(_)) => prior_value.clone(),
+                (None, None) => if current_value.is_null() { prior_value.clone() } else { current_value.clone() },
+            }
+        } else if current_value.is_null() {
+            prior_value.clone()
+        } else {
+            current_value.clone()
+        };
+        blended.insert(key.clone(), value);
+    }
+
+    serde_json::Value::Object(blended)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct HittingStats {
-    pub games_played: u8,
-    pub ground_outs: u8,
-    pub air_outs: u8,
-    pub runs: u8,
-    pub doubles: u8,
-    pub triples: u8,
-    pub home_runs: u8,
-    pub strike_outs: u8,
-    pub base_on_balls: u8,
-    pub intentional_walks: u8,
-    pub hits: u8,
-    pub hit_by_pitch: u8,
-    pub avg: f32,
+    pub games_played: u16,
+    pub ground_outs: u16,
+    pub air_outs: u16,
+    pub runs: u16,
+    pub doubles: u16,
+    pub triples: u16,
+    pub home_runs: u16,
+    pub strike_outs: u16,
+    pub base_on_balls: u16,
+    pub intentional_walks: u16,
+    pub hits: u16,
+    pub hit_by_pitch: u16,
+    pub avg: Option<f32>,
     pub at_bats: u16,
-    pub obp: f32,
-    pub slg: f32,
-    pub ops: f32,
-    pub caught_stealing: u8,
-    pub stolen_bases: u8,
-    pub stolen_base_percentage: f32,
-    pub ground_into_double_play: u8,
+    pub obp: Option<f32>,
+    pub slg: Option<f32>,
+    pub ops: Option<f32>,
+    pub caught_stealing: u16,
+    pub stolen_bases: u16,
+    pub stolen_base_percentage: Option<f32>,
+    pub ground_into_double_play: u16,
     pub number_of_pitches: u16,
     pub plate_appearances: u16,
     pub total_bases: u16,
-    pub rbi: u8,
-    pub left_on_base: u8,
-    pub sac_bunts: u8,
-    pub sac_flies: u8,
-    pub babip: f32,
-    pub ground_outs_to_airouts: f32,
-    pub at_bats_per_home_run: f32,
+    pub rbi: u16,
+    pub left_on_base: u16,
+    pub sac_bunts: u16,
+    pub sac_flies: u16,
+    pub babip: Option<f32>,
+    pub ground_outs_to_airouts: Option<f32>,
+    pub at_bats_per_home_run: Option<f32>,
 }
 
-impl<'de> Deserialize<'de> for HittingStats {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let value = serde_json::Value::deserialize(deserializer)?;
-
-        Ok(HittingStats {
-            games_played: value["gamesPlayed"].as_u64().unwrap_or(0) as u8,
-            ground_outs: value["groundOuts"].as_u64().unwrap_or(0) as u8,
-            air_outs: value["airOuts"].as_u64().unwrap_or(0) as u8,
-            runs: value["runs"].as_u64().unwrap_or(0) as u8,
-            doubles: value["doubles"].as_u64().unwrap_or(0) as u8,
-            triples: value["triples"].as_u64().unwrap_or(0) as u8,
-            home_runs: value["homeRuns"].as_u64().unwrap_or(0) as u8,
-            strike_outs: value["strikeOuts"].as_u64().unwrap_or(0) as u8,
-            base_on_balls: value["baseOnBalls"].as_u64().unwrap_or(0) as u8,
-            intentional_walks: value["intentionalWalks"].as_u64().unwrap_or(0) as u8,
-            hits: value["hits"].as_u64().unwrap_or(0) as u8,
-            hit_by_pitch: value["hitByPitch"].as_u64().unwrap_or(0) as u8,
-            avg: parse_mlb_percentage(&value["avg"]).map_err(serde::de::Error::custom)?,
+impl HittingStats {
+    /// Recover the counts that can wrap over a 30-day window (hits, strikeouts, ground outs and air outs) in stats saved
+    /// while they were stored as `u8`, from the rate stats. Counts that didn't wrap are left as they are.
+    pub fn unwrap_counts(&mut self) {
+        let at_bats = self.at_bats as f32;
+        let home_runs = self.home_runs as f32;
+
+        self.hits = unwrap_count(self.hits, self.avg.map(|avg| avg * at_bats));
+        let hits = self.hits as f32;
+        // babip = (H - HR) / (AB - K - HR + SF)
+        let strikeouts = self.babip
+            .and_then(|babip| ratio(hits - home_runs, babip))
+            .map(|balls_in_play| at_bats - home_runs + self.sac_flies as f32 - balls_in_play);
+        self.strike_outs = unwrap_count(self.strike_outs, strikeouts);
+
+        let outs_in_play = at_bats - hits - self.strike_outs as f32;
+        self.ground_outs = unwrap_count(self.ground_outs, self.ground_outs_to_airouts.map(|ratio| outs_in_play * ratio / (1.0 + ratio)));
+        self.air_outs = unwrap_count(self.air_outs, self.ground_outs_to_airouts.map(|ratio| outs_in_play / (1.0 + ratio)));
+    }
+
+    /// Compute any rates the API left undefined from the underlying counts, where the counts allow it.
+    fn fill_missing_rates(&mut self) {
+        let hits = self.hits as f32;
+        let at_bats = self.at_bats as f32;
+        let walks = self.base_on_balls as f32;
+        let hit_by_pitch = self.hit_by_pitch as f32;
+        let sac_flies = self.sac_flies as f32;
+        let home_runs = self.home_runs as f32;
+
+        self.avg = self.avg.or_else(|| ratio(hits, at_bats));
+        self.obp = self.obp.or_else(|| ratio(hits + walks + hit_by_pitch, at_bats + walks + hit_by_pitch + sac_flies));
+        self.slg = self.slg.or_else(|| ratio(self.total_bases as f32, at_bats));
+        self.ops = self.ops.or_else(|| Some(self.obp? + self.slg?));
+        self.stolen_base_percentage = self.stolen_base_percentage.or_else(|| ratio(self.stolen_bases as f32, (self.stolen_bases + self.caught_stealing) as f32));
+        self.babip = self.babip.or_else(|| ratio(hits - home_runs, at_bats - self.strike_outs as f32 - home_runs + sac_flies));
+        self.ground_outs_to_airouts = self.ground_outs_to_airouts.or_else(|| ratio(self.ground_outs as f32, self.air_outs as f32));
+        self.at_bats_per_home_run = self.at_bats_per_home_run.or_else(|| ratio(at_bats, home_runs));
+    }
+
+    /// Parse a stat object as sent by the MLB API.
+    pub fn from_mlb(value: &serde_json::Value) -> Self {
+        let mut stats = HittingStats {
+            games_played: value["gamesPlayed"].as_u64().unwrap_or(0) as u16,
+            ground_outs: value["groundOuts"].as_u64().unwrap_or(0) as u16,
+            air_outs: value["airOuts"].as_u64().unwrap_or(0) as u16,
+            runs: value["runs"].as_u64().unwrap_or(0) as u16,
+            doubles: value["doubles"].as_u64().unwrap_or(0) as u16,
+            triples: value["triples"].as_u64().unwrap_or(0) as u16,
+            home_runs: value["homeRuns"].as_u64().unwrap_or(0) as u16,
+            strike_outs: value["strikeOuts"].as_u64().unwrap_or(0) as u16,
+            base_on_balls: value["baseOnBalls"].as_u64().unwrap_or(0) as u16,
+            intentional_walks: value["intentionalWalks"].as_u64().unwrap_or(0) as u16,
+            hits: value["hits"].as_u64().unwrap_or(0) as u16,
+            hit_by_pitch: value["hitByPitch"].as_u64().unwrap_or(0) as u16,
+            avg: parse_mlb_percentage(&value["avg"]),
             at_bats: value["atBats"].as_u64().unwrap_or(0) as u16,
-            obp: parse_mlb_percentage(&value["obp"]).map_err(serde::de::Error::custom)?,
-            slg: parse_mlb_percentage(&value["slg"]).map_err(serde::de::Error::custom)?,
-            ops: parse_mlb_percentage(&value["ops"]).map_err(serde::de::Error::custom)?,
-            caught_stealing: value["caughtStealing"].as_u64().unwrap_or(0) as u8,
-            stolen_bases: value["stolenBases"].as_u64().unwrap_or(0) as u8,
-            stolen_base_percentage: parse_mlb_percentage(&value["stolenBasePercentage"]).map_err(serde::de::Error::custom)?,
-            ground_into_double_play: value["groundIntoDoublePlay"].as_u64().unwrap_or(0) as u8,
+            obp: parse_mlb_percentage(&value["obp"]),
+            slg: parse_mlb_percentage(&value["slg"]),
+            ops: parse_mlb_percentage(&value["ops"]),
+            caught_stealing: value["caughtStealing"].as_u64().unwrap_or(0) as u16,
+            stolen_bases: value["stolenBases"].as_u64().unwrap_or(0) as u16,
+            stolen_base_percentage: parse_mlb_percentage(&value["stolenBasePercentage"]),
+            ground_into_double_play: value["groundIntoDoublePlay"].as_u64().unwrap_or(0) as u16,
             number_of_pitches: value["numberOfPitches"].as_u64().unwrap_or(0) as u16,
             plate_appearances: value["plateAppearances"].as_u64().unwrap_or(0) as u16,
             total_bases: value["totalBases"].as_u64().unwrap_or(0) as u16,
-            rbi: value["rbi"].as_u64().unwrap_or(0) as u8,
-            left_on_base: value["leftOnBase"].as_u64().unwrap_or(0) as u8,
-            sac_bunts: value["sacBunts"].as_u64().unwrap_or(0) as u8,
-            sac_flies: value["sacFlies"].as_u64().unwrap_or(0) as u8,
-            babip: parse_mlb_percentage(&value["babip"]).map_err(serde::de::Error::custom)?,
-            ground_outs_to_airouts: parse_mlb_percentage(&value["groundOutsToAirouts"]).map_err(serde::de::Error::custom)?,
-            at_bats_per_home_run: parse_mlb_percentage(&value["atBatsPerHomeRun"]).map_err(serde::de::Error::custom)?,
-        })
+            rbi: value["rbi"].as_u64().unwrap_or(0) as u16,
+            left_on_base: value["leftOnBase"].as_u64().unwrap_or(0) as u16,
+            sac_bunts: value["sacBunts"].as_u64().unwrap_or(0) as u16,
+            sac_flies: value["sacFlies"].as_u64().unwrap_or(0) as u16,
+            babip: parse_mlb_percentage(&value["babip"]),
+            ground_outs_to_airouts: parse_mlb_percentage(&value["groundOutsToAirouts"]),
+            at_bats_per_home_run: parse_mlb_percentage(&value["atBatsPerHomeRun"]),
+        };
+        stats.fill_missing_rates();
+
+        stats
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PitchingStats {
-    pub games_played: u8,
-    pub games_started: u8,
-    pub ground_outs: u8,
-    pub air_outs: u8,
-    pub runs: u8,
-    pub doubles: u8,
-    pub triples: u8,
-    pub home_runs: u8,
-    pub strike_outs: u8,
-    pub base_on_balls: u8,
-    pub intentional_walks: u8,
-    pub hits: u8,
-    pub hit_by_pitch: u8,
-    pub avg: f32,
+    pub games_played: u16,
+    pub games_started: u16,
+    pub ground_outs: u16,
+    pub air_outs: u16,
+    pub runs: u16,
+    pub doubles: u16,
+    pub triples: u16,
+    pub home_runs: u16,
+    pub strike_outs: u16,
+    pub base_on_balls: u16,
+    pub intentional_walks: u16,
+    pub hits: u16,
+    pub hit_by_pitch: u16,
+    pub avg: Option<f32>,
     pub at_bats: u16,
-    pub era: f32,
-    pub innings_pitched: f32,
-    pub wins: u8,
-    pub losses: u8,
-    pub ties: u8,
-    pub saves: u8,
-    pub save_opportunities: u8,
-    pub holds: u8,
-    pub blown_saves: u8,
-    pub earned_runs: u8,
-    pub whip: f32,
+    pub era: Option<f32>,
+    pub innings_pitched: Option<f32>,
+    pub wins: u16,
+    pub losses: u16,
+    pub ties: u16,
+    pub saves: u16,
+    pub save_opportunities: u16,
+    pub holds: u16,
+    pub blown_saves: u16,
+    pub earned_runs: u16,
+    pub whip: Option<f32>,
     pub batters_faced: u16,
     pub outs: u16,
-    pub games_pitched: u8,
-    pub complete_games: u8,
-    pub shutouts: u8,
+    pub games_pitched: u16,
+    pub complete_games: u16,
+    pub shutouts: u16,
     pub strikes: u16,
-    pub strike_percentage: f32,
-    pub hit_batsmen: u8,
-    pub balks: u8,
-    pub wild_pitches: u8,
-    pub pickoffs: u8,
-    pub ground_outs_to_airouts: f32,
-    pub win_percentage: f32,
-    pub pitches_per_inning: f32,
-    pub games_finished: u8,
-    pub strikeout_walk_ratio: f32,
-    pub strikeouts_per_9inn: f32,
-    pub walks_per_9inn: f32,
-    pub hits_per_9inn: f32,
-    pub runs_scored_per_9: f32,
-    pub home_runs_per_9: f32,
-    pub sac_bunts: u8,
-    pub sac_flies: u8,
+    pub strike_percentage: Option<f32>,
+    pub hit_batsmen: u16,
+    pub balks: u16,
+    pub wild_pitches: u16,
+    pub pickoffs: u16,
+    pub ground_outs_to_airouts: Option<f32>,
+    pub win_percentage: Option<f32>,
+    pub pitches_per_inning: Option<f32>,
+    pub games_finished: u16,
+    pub strikeout_walk_ratio: Option<f32>,
+    pub strikeouts_per_9inn: Option<f32>,
+    pub walks_per_9inn: Option<f32>,
+    pub hits_per_9inn: Option<f32>,
+    pub runs_scored_per_9: Option<f32>,
+    pub home_runs_per_9: Option<f32>,
+    pub sac_bunts: u16,
+    pub sac_flies: u16,
 }
 
-impl<'de> Deserialize<'de> for PitchingStats {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let value = serde_json::Value::deserialize(deserializer)?;
-        
-        Ok(PitchingStats {
-            games_played: value["gamesPlayed"].as_u64().unwrap_or(0) as u8,
-            games_started: value["gamesStarted"].as_u64().unwrap_or(0) as u8,
-            ground_outs: value["groundOuts"].as_u64().unwrap_or(0) as u8,
-            air_outs: value["airOuts"].as_u64().unwrap_or(0) as u8,
-            runs: value["runs"].as_u64().unwrap_or(0) as u8,
-            doubles: value["doubles"].as_u64().unwrap_or(0) as u8,
-            triples: value["triples"].as_u64().unwrap_or(0) as u8,
-            home_runs: value["homeRuns"].as_u64().unwrap_or(0) as u8,
-            strike_outs: value["strikeOuts"].as_u64().unwrap_or(0) as u8,
-            base_on_balls: value["baseOnBalls"].as_u64().unwrap_or(0) as u8,
-            intentional_walks: value["intentionalWalks"].as_u64().unwrap_or(0) as u8,
-            hits: value["hits"].as_u64().unwrap_or(0) as u8,
-            hit_by_pitch: value["hitByPitch"].as_u64().unwrap_or(0) as u8,
-            avg: parse_mlb_percentage(&value["avg"]).map_err(serde::de::Error::custom)?,
+impl PitchingStats {
+    /// Recover the counts that can wrap over a 30-day window, as in [`HittingStats::unwrap_counts`].
+    pub fn unwrap_counts(&mut self) {
+        let outs = self.outs as f32;
+
+        self.hits = unwrap_count(self.hits, self.avg.map(|avg| avg * self.at_bats as f32));
+        self.strike_outs = unwrap_count(self.strike_outs, self.strikeouts_per_9inn.map(|strikeouts_per_9| strikeouts_per_9 * outs / 27.0));
+
+        let outs_in_play = outs - self.strike_outs as f32;
+        self.ground_outs = unwrap_count(self.ground_outs, self.ground_outs_to_airouts.map(|ratio| outs_in_play * ratio / (1.0 + ratio)));
+        self.air_outs = unwrap_count(self.air_outs, self.ground_outs_to_airouts.map(|ratio| outs_in_play / (1.0 + ratio)));
+    }
+
+    /// Compute any rates the API left undefined from the underlying counts, where the counts allow it.
+    fn fill_missing_rates(&mut self) {
+        let outs = self.outs as f32;
+        let per_9 = |count: u16| ratio(27.0 * count as f32, outs);
+
+        self.avg = self.avg.or_else(|| ratio(self.hits as f32, self.at_bats as f32));
+        self.era = self.era.or_else(|| per_9(self.earned_runs));
+        self.innings_pitched = self.innings_pitched.or(Some((self.outs / 3) as f32 + (self.outs % 3) as f32 / 10.0));
+        self.whip = self.whip.or_else(|| ratio(3.0 * (self.base_on_balls as f32 + self.hits as f32), outs));
+        self.ground_outs_to_airouts = self.ground_outs_to_airouts.or_else(|| ratio(self.ground_outs as f32, self.air_outs as f32));
+        self.win_percentage = self.win_percentage.or_else(|| ratio(self.wins as f32, (self.wins + self.losses) as f32));
+        self.strikeout_walk_ratio = self.strikeout_walk_ratio.or_else(|| ratio(self.strike_outs as f32, self.base_on_balls as f32));
+        self.strikeouts_per_9inn = self.strikeouts_per_9inn.or_else(|| per_9(self.strike_outs));
+        self.walks_per_9inn = self.walks_per_9inn.or_else(|| per_9(self.base_on_balls));
+        self.hits_per_9inn = self.hits_per_9inn.or_else(|| per_9(self.hits));
+        self.runs_scored_per_9 = self.runs_scored_per_9.or_else(|| per_9(self.runs));
+        self.home_runs_per_9 = self.home_runs_per_9.or_else(|| per_9(self.home_runs));
+    }
+
+    /// Parse a stat object as sent by the MLB API.
+    pub fn from_mlb(value: &serde_json::Value) -> Self {
+        let mut stats = PitchingStats {
+            games_played: value["gamesPlayed"].as_u64().unwrap_or(0) as u16,
+            games_started: value["gamesStarted"].as_u64().unwrap_or(0) as u16,
+            ground_outs: value["groundOuts"].as_u64().unwrap_or(0) as u16,
+            air_outs: value["airOuts"].as_u64().unwrap_or(0) as u16,
+            runs: value["runs"].as_u64().unwrap_or(0) as u16,
+            doubles: value["doubles"].as_u64().unwrap_or(0) as u16,
+            triples: value["triples"].as_u64().unwrap_or(0) as u16,
+            home_runs: value["homeRuns"].as_u64().unwrap_or(0) as u16,
+            strike_outs: value["strikeOuts"].as_u64().unwrap_or(0) as u16,
+            base_on_balls: value["baseOnBalls"].as_u64().unwrap_or(0) as u16,
+            intentional_walks: value["intentionalWalks"].as_u64().unwrap_or(0) as u16,
+            hits: value["hits"].as_u64().unwrap_or(0) as u16,
+            hit_by_pitch: value["hitByPitch"].as_u64().unwrap_or(0) as u16,
+            avg: parse_mlb_percentage(&value["avg"]),
             at_bats: value["atBats"].as_u64().unwrap_or(0) as u16,
-            era: parse_mlb_percentage(&value["era"]).map_err(serde::de::Error::custom)?,
-            innings_pitched: parse_mlb_percentage(&value["inningsPitched"]).map_err(serde::de::Error::custom)?,
-            wins: value["wins"].as_u64().unwrap_or(0) as u8,
-            losses: value["losses"].as_u64().unwrap_or(0) as u8,
-            ties: value["ties"].as_u64().unwrap_or(0) as u8,
-            saves: value["saves"].as_u64().unwrap_or(0) as u8,
-            save_opportunities: value["saveOpportunities"].as_u64().unwrap_or(0) as u8,
-            holds: value["holds"].as_u64().unwrap_or(0) as u8,
-            blown_saves: value["blownSaves"].as_u64().unwrap_or(0) as u8,
-            earned_runs: value["earnedRuns"].as_u64().unwrap_or(0) as u8,
-            whip: parse_mlb_percentage(&value["whip"]).map_err(serde::de::Error::custom)?,
+            era: parse_mlb_percentage(&value["era"]),
+            innings_pitched: parse_mlb_percentage(&value["inningsPitched"]),
+            wins: value["wins"].as_u64().unwrap_or(0) as u16,
+            losses: value["losses"].as_u64().unwrap_or(0) as u16,
+            ties: value["ties"].as_u64().unwrap_or(0) as u16,
+            saves: value["saves"].as_u64().unwrap_or(0) as u16,
+            save_opportunities: value["saveOpportunities"].as_u64().unwrap_or(0) as u16,
+            holds: value["holds"].as_u64().unwrap_or(0) as u16,
+            blown_saves: value["blownSaves"].as_u64().unwrap_or(0) as u16,
+            earned_runs: value["earnedRuns"].as_u64().unwrap_or(0) as u16,
+            whip: parse_mlb_percentage(&value["whip"]),
             batters_faced: value["battersFaced"].as_u64().unwrap_or(0) as u16,
             outs: value["outs"].as_u64().unwrap_or(0) as u16,
-            games_pitched: value["gamesPitched"].as_u64().unwrap_or(0) as u8,
-            complete_games: value["completeGames"].as_u64().unwrap_or(0) as u8,
-            shutouts: value["shutouts"].as_u64().unwrap_or(0) as u8,
+            games_pitched: value["gamesPitched"].as_u64().unwrap_or(0) as u16,
+            complete_games: value["completeGames"].as_u64().unwrap_or(0) as u16,
+            shutouts: value["shutouts"].as_u64().unwrap_or(0) as u16,
             strikes: value["strikes"].as_u64().unwrap_or(0) as u16,
-            strike_percentage: parse_mlb_percentage(&value["strikePercentage"]).map_err(serde::de::Error::custom)?,
-            hit_batsmen: value["hitBatsmen"].as_u64().unwrap_or(0) as u8,
-            balks: value["balks"].as_u64().unwrap_or(0) as u8,
-            wild_pitches: value["wildPitches"].as_u64().unwrap_or(0) as u8,
-            pickoffs: value["pickoffs"].as_u64().unwrap_or(0) as u8,
-            ground_outs_to_airouts: parse_mlb_percentage(&value["groundOutsToAirouts"]).map_err(serde::de::Error::custom)?,
-            win_percentage: parse_mlb_percentage(&value["winPercentage"]).map_err(serde::de::Error::custom)?,
-            pitches_per_inning: parse_mlb_percentage(&value["pitchesPerInning"]).map_err(serde::de::Error::custom)?,
-            games_finished: value["gamesFinished"].as_u64().unwrap_or(0) as u8,
-            strikeout_walk_ratio: parse_mlb_percentage(&value["strikeoutWalkRatio"]).map_err(serde::de::Error::custom)?,
-            strikeouts_per_9inn: parse_mlb_percentage(&value["strikeoutsPer9Inn"]).map_err(serde::de::Error::custom)?,
-            walks_per_9inn: parse_mlb_percentage(&value["walksPer9Inn"]).map_err(serde::de::Error::custom)?,
-            hits_per_9inn: parse_mlb_percentage(&value["hitsPer9Inn"]).map_err(serde::de::Error::custom)?,
-            runs_scored_per_9: parse_mlb_percentage(&value["runsScoredPer9"]).map_err(serde::de::Error::custom)?,
-            home_runs_per_9: parse_mlb_percentage(&value["homeRunsPer9"]).map_err(serde::de::Error::custom)?,
-            sac_bunts: value["sacBunts"].as_u64().unwrap_or(0) as u8,
-            sac_flies: value["sacFlies"].as_u64().unwrap_or(0) as u8,
-        })
+            strike_percentage: parse_mlb_percentage(&value["strikePercentage"]),
+            hit_batsmen: value["hitBatsmen"].as_u64().unwrap_or(0) as u16,
+            balks: value["balks"].as_u64().unwrap_or(0) as u16,
+            wild_pitches: value["wildPitches"].as_u64().unwrap_or(0) as u16,
+            pickoffs: value["pickoffs"].as_u64().unwrap_or(0) as u16,
+            ground_outs_to_airouts: parse_mlb_percentage(&value["groundOutsToAirouts"]),
+            win_percentage: parse_mlb_percentage(&value["winPercentage"]),
+            pitches_per_inning: parse_mlb_percentage(&value["pitchesPerInning"]),
+            games_finished: value["gamesFinished"].as_u64().unwrap_or(0) as u16,
+            strikeout_walk_ratio: parse_mlb_percentage(&value["strikeoutWalkRatio"]),
+            strikeouts_per_9inn: parse_mlb_percentage(&value["strikeoutsPer9Inn"]),
+            walks_per_9inn: parse_mlb_percentage(&value["walksPer9Inn"]),
+            hits_per_9inn: parse_mlb_percentage(&value["hitsPer9Inn"]),
+            runs_scored_per_9: parse_mlb_percentage(&value["runsScoredPer9"]),
+            home_runs_per_9: parse_mlb_percentage(&value["homeRunsPer9"]),
+            sac_bunts: value["sacBunts"].as_u64().unwrap_or(0) as u16,
+            sac_flies: value["sacFlies"].as_u64().unwrap_or(0) as u16,
+        };
+        stats.fill_missing_rates();
+
+        stats
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct FieldingStats {
-    pub games_played: u8,
-    pub games_started: u8,
+    pub games_played: u16,
+    pub games_started: u16,
     pub assists: u16,
     pub put_outs: u16,
-    pub errors: u8,
+    pub errors: u16,
     pub chances: u16,
-    pub fielding: f32,
-    pub range_factor_per_game: f32,
-    pub range_factor_per_9inn: f32,
-    pub innings: f32,
-    pub games: u8,
-    pub double_plays: u8,
-    pub triple_plays: u8,
-    pub throwing_errors: u8,
+    pub fielding: Option<f32>,
+    pub range_factor_per_game: Option<f32>,
+    pub range_factor_per_9inn: Option<f32>,
+    pub innings: Option<f32>,
+    pub games: u16,
+    pub double_plays: u16,
+    pub triple_plays: u16,
+    pub throwing_errors: u16,
 }
 
-impl<'de> Deserialize<'de> for FieldingStats {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let value = serde_json::Value::deserialize(deserializer)?;
-        
-        Ok(FieldingStats {
-            games_played: value["gamesPlayed"].as_u64().unwrap_or(0) as u8,
-            games_started: value["gamesStarted"].as_u64().unwrap_or(0) as u8,
+impl FieldingStats {
+    /// Compute any rates the API left undefined from the underlying counts, where the counts allow it.
+    fn fill_missing_rates(&mut self) {
+        let plays = (self.put_outs + self.assists) as f32;
+
+        self.fielding = self.fielding.or_else(|| ratio(plays, self.chances as f32));
+        self.range_factor_per_game = self.range_factor_per_game.or_else(|| ratio(plays, self.games as f32));
+        self.range_factor_per_9inn = self.range_factor_per_9inn.or_else(|| ratio(9.0 * plays, parsed_innings_to_outs(self.innings?) / 3.0));
+    }
+
+    /// Parse a stat object as sent by the MLB API.
+    pub fn from_mlb(value: &serde_json::Value) -> Self {
+        let mut stats = FieldingStats {
+            games_played: value["gamesPlayed"].as_u64().unwrap_or(0) as u16,
+            games_started: value["gamesStarted"].as_u64().unwrap_or(0) as u16,
             assists: value["assists"].as_u64().unwrap_or(0) as u16,
             put_outs: value["putOuts"].as_u64().unwrap_or(0) as u16,
-            errors: value["errors"].as_u64().unwrap_or(0) as u8,
+            errors: value["errors"].as_u64().unwrap_or(0) as u16,
             chances: value["chances"].as_u64().unwrap_or(0) as u16,
-            fielding: parse_mlb_percentage(&value["fielding"]).map_err(serde::de::Error::custom)?,
-            range_factor_per_game: parse_mlb_percentage(&value["rangeFactorPerGame"]).map_err(serde::de::Error::custom)?,
-            range_factor_per_9inn: parse_mlb_percentage(&value["rangeFactorPer9Inn"]).map_err(serde::de::Error::custom)?,
-            innings: parse_mlb_percentage(&value["innings"]).map_err(serde::de::Error::custom)?,
-            games: value["games"].as_u64().unwrap_or(0) as u8,
-            double_plays: value["doublePlays"].as_u64().unwrap_or(0) as u8,
-            triple_plays: value["triplePlays"].as_u64().unwrap_or(0) as u8,
-            throwing_errors: value["throwingErrors"].as_u64().unwrap_or(0) as u8,
-        })
+            fielding: parse_mlb_percentage(&value["fielding"]),
+            range_factor_per_game: parse_mlb_percentage(&value["rangeFactorPerGame"]),
+            range_factor_per_9inn: parse_mlb_percentage(&value["rangeFactorPer9Inn"]),
+            innings: parse_mlb_percentage(&value["innings"]),
+            games: value["games"].as_u64().unwrap_or(0) as u16,
+            double_plays: value["doublePlays"].as_u64().unwrap_or(0) as u16,
+            triple_plays: value["triplePlays"].as_u64().unwrap_or(0) as u16,
+            throwing_errors: value["throwingErrors"].as_u64().unwrap_or(0) as u16,
+        };
+        stats.fill_missing_rates();
+
+        stats
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Date {
     pub year: u16,
     pub month: u8,
     pub day: u8,
 }
 
-impl ToString for Date {
-    fn to_string(&self) -> String {
-        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl std::str::FromStr for Date {
+    type Err = String;
+
+    /// Parse a `YYYY-MM-DD` date.
+    fn from_str(date: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = date.split('-').collect();
+        let [year, month, day] = parts.as_slice() else {
+            return Err(format!("Invalid date {}, expected YYYY-MM-DD", date));
+        };
+        let invalid = |_| format!("Invalid date {}, expected YYYY-MM-DD", date);
+
+        Ok(Date {
+            year: year.parse().map_err(invalid)?,
+            month: month.parse().map_err(invalid)?,
+            day: day.parse().map_err(invalid)?,
+        })
     }
 }
 
@@ -318,111 +521,294 @@ impl Date {
         }
         date
     }
+
+    /// The number of days from `earlier` to this date.
+    pub fn days_since(&self, earlier: &Date) -> i32 {
+        self.day_number() - earlier.day_number()
+    }
+
+    /// Days since 1970-01-01, counting leap years.
+    fn day_number(&self) -> i32 {
+        // shift the year to start in March, so that leap days fall at the end of it
+        let year = self.year as i32 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = (self.month as i32 + 9) % 12;
+        let day_of_year = (153 * month + 2) / 5 + self.day as i32 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        era * 146097 + day_of_era - 719468
+    }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Team {
     pub team_id: u8,
     pub batting_stats: HittingStats,
     pub pitching_stats: PitchingStats,
     pub fielding_stats: FieldingStats,
+    /// The team's probable starting pitcher for the game, if one was announced.
+    pub starting_pitcher: Option<StartingPitcher>,
+    pub bullpen: Option<Bullpen>,
+    /// Set by [`crate::history::derive_features`].
+    pub rest: Option<Rest>,
+    /// Only fetched if [`FetchOptions::splits`] is set.
+    pub splits: Option<Splits>,
+    /// The team's standing on the morning of the game.
+    pub standing: Option<Standing>,
+    /// Set by [`crate::history::derive_features`].
+    pub ratings: Option<Ratings>,
+    /// Set by [`crate::history::derive_features`].
+    pub expected_wins: Option<ExpectedWins>,
 }
 
 impl Team {
     pub async fn by_date(team_id: u8, end_date: &Date) -> Result<Self, String> {
+        let url = Team::window_url(team_id, end_date);
+        let stats = fetch_json(&url).await?;
+
+        Team::from_stat_values(
+            team_id,
+            &stats["stats"][0]["splits"][0]["stat"],
+            &stats["stats"][1]["splits"][0]["stat"],
+            &stats["stats"][2]["splits"][0]["stat"],
+            &url,
+        )
+    }
+
+    /// Get the team's stats over the 30 days before `end_date`, blended with `prior_games` games' worth
+    /// of its stats from the previous season.
+    ///
+    /// The prior season's weight shrinks as the window fills up, so opening-week snapshots lean on last
+    /// season while mid-season snapshots are dominated by the window. An empty window (e.g. on opening day)
+    /// yields the prior season's stats alone.
+    pub async fn by_date_blended(team_id: u8, end_date: &Date, prior_games: f32) -> Result<Self, String> {
+        let url = Team::window_url(team_id, end_date);
+        let stats = fetch_json(&url).await?;
+
+        let prior_url = format!(
+            "https://statsapi.mlb.com/api/v1/teams/{}/stats?season={}&stats=season&group=hitting,pitching,fielding",
+            team_id,
+            end_date.year - 1,
+        );
+        let prior_stats = fetch_json(&prior_url).await?;
+
+        let blended: Vec<serde_json::Value> = (0..3)
+            .map(|i| blend_stat_values(
+                &stats["stats"][i]["splits"][0]["stat"],
+                &prior_stats["stats"][i]["splits"][0]["stat"],
+                prior_games,
+            ))
+            .collect();
+
+        Team::from_stat_values(team_id, &blended[0], &blended[1], &blended[2], &url)
+    }
+
+    /// Get the team's stats as of `end_date`, blending in the prior season and adding split stats if `options` asks
+    /// for them.
+    pub async fn fetch(team_id: u8, end_date: &Date, options: &FetchOptions) -> Result<Self, String> {
+        let mut team = match options.prior_season_games {
+            Some(prior_games) => Team::by_date_blended(team_id, end_date, prior_games).await?,
+            None => Team::by_date(team_id, end_date).await?,
+        };
+        if options.splits {
+            team.splits = Some(Splits::by_date(team_id, end_date).await?);
+        }
+
+        Ok(team)
+    }
+
+    /// Recover the counts of every stat line of the team that wrapped while counting stats were stored as `u8` (see
+    /// [`HittingStats::unwrap_counts`]).
+    pub fn unwrap_counts(&mut self) {
+        self.batting_stats.unwrap_counts();
+        self.pitching_stats.unwrap_counts();
+        if let Some(splits) = &mut self.splits {
+            let hitting = [&mut splits.home_hitting, &mut splits.road_hitting, &mut splits.vs_left_hitting, &mut splits.vs_right_hitting];
+            for stats in hitting.into_iter().flatten() {
+                stats.unwrap_counts();
+            }
+            for stats in [&mut splits.home_pitching, &mut splits.road_pitching].into_iter().flatten() {
+                stats.unwrap_counts();
+            }
+        }
+    }
+
+    fn window_url(team_id: u8, end_date: &Date) -> String {
         let season = end_date.year;
         let start_date = end_date.minus_n_days(30);
-        let url = format!(
+        format!(
             "https://statsapi.mlb.com/api/v1/teams/{}/stats?season={}&stats=byDateRange&group=hitting,pitching,fielding&startDate={}&endDate={}",
             team_id,
             season,
-            start_date.to_string(),
-            end_date.to_string(),
-        );
-        //let stats = reqwest::get(&url).await.unwrap().json::<serde_json::Value>().await.unwrap();
-        let stats = match send_request(&url, 5).await {
-            Ok(stats) => stats.json::<serde_json::Value>().await.unwrap(),
-            Err(e) => return Err(e),
-        };
-        // println!("{:#?}", stats);
+            start_date,
+            end_date,
+        )
+    }
 
-        let batting_stats = match serde_json::from_value(stats["stats"][0]["splits"][0]["stat"].clone()) {
-            Ok(stats) => stats,
-            Err(_) => return Err(format!("Failed to parse batting stats: {url}")),
-        };
-        let pitching_stats = match serde_json::from_value(stats["stats"][1]["splits"][0]["stat"].clone()) {
-            Ok(stats) => stats,
-            Err(_) => return Err(format!("Failed to parse pitching stats: {url}")),
-        };
-        let fielding_stats = match serde_json::from_value(stats["stats"][2]["splits"][0]["stat"].clone()) {
-            Ok(stats) => stats,
-            Err(_) => return Err(format!("Failed to parse fielding stats: {url}")),
-        };
+    fn from_stat_values(
+        team_id: u8,
+        batting: &serde_json::Value,
+        pitching: &serde_json::Value,
+        fielding: &serde_json::Value,
+        url: &str,
+    ) -> Result<Self, String> {
+        // a team with no games in the window has no stats at all
+        if !batting.is_object() {
+            return Err(format!("Failed to parse batting stats: {url}"));
+        }
+        if !pitching.is_object() {
+            return Err(format!("Failed to parse pitching stats: {url}"));
+        }
+        if !fielding.is_object() {
+            return Err(format!("Failed to parse fielding stats: {url}"));
+        }
 
         Ok(Team {
             team_id,
-            batting_stats,
-            pitching_stats,
-            fielding_stats,
+            batting_stats: HittingStats::from_mlb(batting),
+            pitching_stats: PitchingStats::from_mlb(pitching),
+            fielding_stats: FieldingStats::from_mlb(fielding),
+            starting_pitcher: None,
+            bullpen: None,
+            rest: None,
+            splits: None,
+            standing: None,
+            ratings: None,
+            expected_wins: None,
         })
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GameResult {
     pub home_team_score: u8,
     pub away_team_score: u8,
+    pub linescore: Option<Linescore>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GameSetting {
     pub date: Date,
+    pub game_pk: Option<u32>,
+    pub venue: Option<Venue>,
+    /// Runs scored at the venue relative to the two teams' road averages (1.0 is neutral), estimated from the stored
+    /// games before this one. This is set by [`crate::history::derive_features`].
+    pub park_factor: Option<f32>,
+    pub day_night: Option<DayNight>,
+    pub start_time: Option<LocalTime>,
+    pub weather: Option<Weather>,
+    /// The number of innings the game was scheduled for (7 for the 2020-21 doubleheaders).
+    pub scheduled_innings: Option<u8>,
+    pub double_header: Option<DoubleHeader>,
+    /// Which game of the day this was for the two teams (2 for the second game of a doubleheader).
+    pub game_number: Option<u8>,
+    /// From the schedule, so it's only there for games fetched since it was recorded. It's stored under a new name
+    /// because `derive` used to infer a `series` from the stored games, which is now ignored.
+    pub scheduled_series: Option<SeriesContext>,
+    /// The last day covered by the teams' stat windows, the day before the game. `None` for games saved while the
+    /// windows ended on the game's date, and so included it; `cargo run refetch` brings those in line.
+    pub stats_through: Option<Date>,
 }
 
-#[derive(Debug, Serialize)]
+/// Options controlling how games are fetched from the MLB API.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FetchOptions {
+    /// If set, blend each team's 30-day window with this many games' worth of its prior-season stats
+    /// (see [`Team::by_date_blended`]). This also keeps the first date of the season, which is otherwise skipped.
+    pub prior_season_games: Option<f32>,
+    /// If set, also fetch each team's home/road and vs-left/vs-right split stats (see [`Splits`]).
+    pub splits: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Game {
     pub setting: GameSetting,
     pub home_team: Team,
     pub away_team: Team,
     pub result: GameResult,
+    /// Set by [`crate::history::derive_features`].
+    pub head_to_head: Option<HeadToHead>,
 }
 
 impl Game {
-    pub async fn from_value(game_value: &serde_json::Value) -> Result<Self, String> {
-        let game = &game_value["games"][0];
-
+    /// Fetch the data for a finished game from its entry in the MLB schedule.
+    pub async fn from_value(game: &serde_json::Value, options: &FetchOptions) -> Result<Self, String> {
         let status_code = game["status"]["statusCode"].as_str().unwrap();
         if status_code != "F" {
             return Err("Game is not finished".to_string());
         }
 
         let date = Date::from(game["officialDate"].as_str().unwrap());
-        let setting = GameSetting { date };
+        let venue = match game["venue"]["id"].as_u64() {
+            Some(venue_id) => Some(Venue::get(venue_id as u16).await?),
+            None => None,
+        };
+        let game_pk = game["gamePk"].as_u64().map(|game_pk| game_pk as u32);
+        // the start time and weather are optional, so a failed live feed leaves them empty rather than losing the game
+        let game_time = match game_pk {
+            Some(game_pk) => GameTime::fetch(game_pk).await.ok(),
+            None => None,
+        };
+        let setting = GameSetting {
+            date,
+            game_pk,
+            venue,
+            park_factor: None,
+            day_night: game["dayNight"].as_str().and_then(DayNight::from_mlb),
+            start_time: game_time.as_ref().and_then(|game_time| game_time.start_time),
+            weather: game_time.and_then(|game_time| game_time.weather),
+            scheduled_innings: game["scheduledInnings"].as_u64().map(|innings| innings as u8),
+            double_header: game["doubleHeader"].as_str().and_then(DoubleHeader::from_mlb),
+            game_number: game["gameNumber"].as_u64().map(|game_number| game_number as u8),
+            scheduled_series: SeriesContext::from_mlb(game),
+            stats_through: Some(date.previous_day()),
+        };
 
         let home_team_id = game["teams"]["home"]["team"]["id"].as_u64().unwrap() as u8;
         let away_team_id = game["teams"]["away"]["team"]["id"].as_u64().unwrap() as u8;
 
-        let home_team = Team::by_date(home_team_id, &date).await?;
-        let away_team = Team::by_date(away_team_id, &date).await?;
+        // the windows end the day before, so they never include the game itself (or an earlier game that day)
+        let mut home_team = Team::fetch(home_team_id, &date.previous_day(), options).await?;
+        let mut away_team = Team::fetch(away_team_id, &date.previous_day(), options).await?;
+
+        if let Some(player_id) = game["teams"]["home"]["probablePitcher"]["id"].as_u64() {
+            home_team.starting_pitcher = Some(StartingPitcher::by_date(player_id as u32, &date).await?);
+        }
+        if let Some(player_id) = game["teams"]["away"]["probablePitcher"]["id"].as_u64() {
+            away_team.starting_pitcher = Some(StartingPitcher::by_date(player_id as u32, &date).await?);
+        }
+
+        home_team.bullpen = Some(Bullpen::by_date(home_team_id, &date).await?);
+        away_team.bullpen = Some(Bullpen::by_date(away_team_id, &date).await?);
+
+        home_team.standing = Standings::by_date(home_team_id, &date).await?;
+        away_team.standing = Standings::by_date(away_team_id, &date).await?;
 
         let home_team_score = match game["teams"]["home"]["score"].as_u64() {
             Some(score) => score as u8,
             None => return Err("Failed to parse home team score".to_string()),
         };
         let away_team_score = game["teams"]["away"]["score"].as_u64().unwrap() as u8;
-        let result = GameResult { home_team_score, away_team_score };
+        // the linescore is optional too, so a failed live feed leaves it empty
+        let linescore = match game_pk {
+            Some(game_pk) => Linescore::fetch(game_pk).await.ok(),
+            None => None,
+        };
+        let result = GameResult { home_team_score, away_team_score, linescore };
 
         Ok(Game {
             setting,
             home_team,
             away_team,
             result,
+            head_to_head: None,
         })
     }
 
-    pub async fn get_all_by_team_in_season(team_id: u8, season: u16, show_errors: bool) -> Vec<Self> {
+    pub async fn get_all_by_team_in_season(team_id: u8, season: u16, show_errors: bool, options: &FetchOptions) -> Vec<Self> {
         let url = format!(
-            "https://statsapi.mlb.com/api/v1/schedule?sportId=1&teamId={}&season={}",
+            "https://statsapi.mlb.com/api/v1/schedule?sportId=1&teamId={}&season={}&hydrate=probablePitcher",
             team_id,
             season,
         );
@@ -438,25 +824,60 @@ impl Game {
         };
 
         let mut games = Vec::new();
-        for (i, game) in schedule["dates"].as_array().unwrap().iter().enumerate().progress() {
-            if i == 0 {
+        for (i, date) in schedule["dates"].as_array().unwrap().iter().enumerate().progress() {
+            if i == 0 && options.prior_season_games.is_none() {
                 continue;
             }
-            let game = match Game::from_value(&game).await {
-                Ok(game) => game,
-                Err(e) => {
-                    if show_errors {
-                        eprintln!("{}", e);
+            for game in date["games"].as_array().unwrap() {
+                let game = match Game::from_value(game, options).await {
+                    Ok(game) => game,
+                    Err(e) => {
+                        if show_errors {
+                            eprintln!("{}", e);
+                        }
+                        continue;
                     }
-                    continue;
-                }
-            };
-            games.push(game);
+                };
+                games.push(game);
+            }
         }
 
         games
     }
 
+    /// Load a game saved by [`Game::save`].
+    pub fn load(file_path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(file_path)
+            .map_err(|e| format!("Failed to read file {}: {}", file_path, e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse game {}: {}", file_path, e))
+    }
+
+    /// Whether the teams' stats only cover days before the game (see [`GameSetting::stats_through`]).
+    pub fn has_pregame_stats(&self) -> bool {
+        self.setting.stats_through.is_some_and(|stats_through| stats_through < self.setting.date)
+    }
+
+    /// Re-fetch both teams' stat windows (and splits, if the game has them or `options` asks for them) to end the day
+    /// before the game, for a game saved while they ended on its date. Everything else about the teams is kept.
+    pub async fn refetch_team_stats(&mut self, options: &FetchOptions) -> Result<(), String> {
+        let stats_through = self.setting.date.previous_day();
+        for team in [&mut self.home_team, &mut self.away_team] {
+            let options = FetchOptions { splits: options.splits || team.splits.is_some(), ..*options };
+            let fetched = Team::fetch(team.team_id, &stats_through, &options).await?;
+
+            team.batting_stats = fetched.batting_stats;
+            team.pitching_stats = fetched.pitching_stats;
+            team.fielding_stats = fetched.fielding_stats;
+            if options.splits {
+                team.splits = fetched.splits;
+            }
+        }
+        self.setting.stats_through = Some(stats_through);
+
+        Ok(())
+    }
+
     pub fn save(&self) -> Result<(), String> {
         // Create base directories
         std::fs::create_dir_all(format!(
@@ -465,13 +886,22 @@ impl Game {
             self.home_team.team_id
         )).map_err(|e| format!("Failed to create directories: {}", e))?;
 
-        // Create file path
-        let file_path = format!(
-            "data/{}/{}/{}.json",
-            self.setting.date.year,
-            self.home_team.team_id,
-            self.setting.date.to_string()
-        );
+        // Create file path, suffixed with the game number for the second game of a doubleheader
+        let file_path = match self.setting.game_number {
+            Some(game_number) if game_number > 1 => format!(
+                "data/{}/{}/{}-{}.json",
+                self.setting.date.year,
+                self.home_team.team_id,
+                self.setting.date,
+                game_number
+            ),
+            _ => format!(
+                "data/{}/{}/{}.json",
+                self.setting.date.year,
+                self.home_team.team_id,
+                self.setting.date
+            ),
+        };
 
         // Serialize and save
         let json = serde_json::to_string_pretty(self)
@@ -482,3 +912,95 @@ impl Game {
         Ok(())
     }
 }
+
+#[cfg(test)]
+impl Game {
+    /// A game with nothing but its date, teams and final score, for the tests of the models and derive passes.
+    pub(crate) fn with_score(date: &str, home_team_id: u8, away_team_id: u8, home_team_score: u8, away_team_score: u8) -> Self {
+        let team = |team_id: u8| {
+            let empty = serde_json::json!({});
+            Team::from_stat_values(team_id, &empty, &empty, &empty, "").unwrap()
+        };
+
+        Game {
+            setting: GameSetting {
+                date: Date::from(date),
+                game_pk: None,
+                venue: None,
+                park_factor: None,
+                day_night: None,
+                start_time: None,
+                weather: None,
+                scheduled_innings: None,
+                double_header: None,
+                game_number: None,
+                scheduled_series: None,
+                stats_through: Some(Date::from(date).previous_day()),
+            },
+            home_team: team(home_team_id),
+            away_team: team(away_team_id),
+            result: GameResult { home_team_score, away_team_score, linescore: None },
+            head_to_head: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A 30-day window with 300 hits, 270 strikeouts, 230 ground outs and 200 air outs in 1000 at bats.
+    fn hitting(hits: u16, strike_outs: u16) -> HittingStats {
+        HittingStats::from_mlb(&json!({
+            "atBats": 1000, "hits": hits, "homeRuns": 40, "strikeOuts": strike_outs, "sacFlies": 10,
+            "groundOuts": 230, "airOuts": 200,
+            "avg": ".300", "babip": ".371", "groundOutsToAirouts": "1.15",
+        }))
+    }
+
+    /// 300 strikeouts over 2430 outs (90 innings), with 1000 at bats against and 250 hits.
+    fn pitching(hits: u16, strike_outs: u16) -> PitchingStats {
+        PitchingStats::from_mlb(&json!({
+            "atBats": 1000, "outs": 2430, "hits": hits, "strikeOuts": strike_outs,
+            "groundOuts": 1100, "airOuts": 1030,
+            "avg": ".250", "strikeoutsPer9Inn": "3.33", "groundOutsToAirouts": "1.07",
+        }))
+    }
+
+    #[test]
+    fn counts_stored_as_u16_are_unchanged() {
+        let mut stats = hitting(300, 270);
+        stats.unwrap_counts();
+        assert_eq!((stats.hits, stats.strike_outs, stats.ground_outs, stats.air_outs), (300, 270, 230, 200));
+
+        let mut stats = pitching(250, 300);
+        stats.unwrap_counts();
+        assert_eq!((stats.hits, stats.strike_outs, stats.ground_outs, stats.air_outs), (250, 300, 1100, 1030));
+    }
+
+    #[test]
+    fn wrapped_counts_are_recovered() {
+        // 300 hits and 270 strikeouts as they were stored in a u8
+        let mut stats = hitting(300 - 256, 270 - 256);
+        stats.unwrap_counts();
+        assert_eq!((stats.hits, stats.strike_outs), (300, 270));
+
+        let mut stats = pitching(250, 300 - 256);
+        stats.unwrap_counts();
+        assert_eq!((stats.hits, stats.strike_outs), (250, 300));
+    }
+
+    #[test]
+    fn stats_through_the_game_date_are_not_pregame() {
+        let mut game = Game::with_score("2024-04-02", 147, 111, 5, 3);
+        assert!(game.has_pregame_stats());
+
+        // saved before the cutoff moved: no record of it
+        game.setting.stats_through = None;
+        assert!(!game.has_pregame_stats());
+
+        game.setting.stats_through = Some(Date::from("2024-04-02"));
+        assert!(!game.has_pregame_stats());
+    }
+}
@@ -0,0 +1,534 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::dataset::Dataset;
+use crate::models::{load_json, save_json, Regressor};
+use crate::rng::Rng;
+
+/// The bin of a missing value. Every other value falls in one of the bins below it.
+const MISSING_BIN: u8 = u8::MAX;
+
+/// The bound on a Poisson model's raw score, so a bad tree can't overflow `exp`.
+const MAX_RAW_SCORE: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Loss {
+    SquaredError,
+    /// Poisson deviance, with the trees predicting log runs.
+    Poisson,
+}
+
+/// The knobs of the booster, named like XGBoost's parameters in `training/train.py`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoostingParams {
+    pub loss: Loss,
+    pub learning_rate: f64,
+    pub max_depth: u8,
+    /// The maximum number of boosting rounds.
+    pub num_boost_round: usize,
+    /// Stop once the validation loss hasn't improved for this many rounds.
+    pub early_stopping_rounds: usize,
+    /// The share of rows each tree is fit to.
+    pub subsample: f64,
+    /// The share of features each tree may split on.
+    pub colsample_bytree: f64,
+    /// The minimum sum of hessians in a leaf.
+    pub min_child_weight: f64,
+    /// The L2 penalty on leaf values.
+    pub lambda: f64,
+    /// The maximum number of bins each feature's values are bucketed into (at most 255).
+    pub max_bin: usize,
+    pub seed: u64,
+}
+
+impl Default for BoostingParams {
+    /// The best parameters found by optuna in `training/train.py`.
+    fn default() -> Self {
+        BoostingParams {
+            loss: Loss::SquaredError,
+            learning_rate: 0.013393217973010949,
+            max_depth: 2,
+            num_boost_round: 1000,
+            early_stopping_rounds: 50,
+            subsample: 0.5903139448662046,
+            colsample_bytree: 0.6045142333929849,
+            min_child_weight: 6.0,
+            lambda: 1.0,
+            max_bin: 255,
+            seed: 42,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Node {
+    Leaf {
+        value: f64,
+    },
+    /// Rows whose feature is at most the threshold go left, and missing values go the learned default way.
+    Split {
+        feature: usize,
+        threshold: f64,
+        missing_left: bool,
+        left: usize,
+        right: usize,
+    },
+}
+
+/// A regression tree, with the root first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tree {
+    pub nodes: Vec<Node>,
+}
+
+impl Tree {
+    pub fn predict(&self, row: &[f64]) -> f64 {
+        let mut index = 0;
+        loop {
+            match &self.nodes[index] {
+                Node::Leaf { value } => return *value,
+                Node::Split { feature, threshold, missing_left, left, right } => {
+                    let value = row[*feature];
+                    let goes_left = if value.is_nan() { *missing_left } else { value <= *threshold };
+                    index = if goes_left { *left } else { *right };
+                }
+            }
+        }
+    }
+}
+
+/// A histogram-based gradient-boosted tree ensemble, with a separate ensemble per target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradientBoosting {
+    pub params: BoostingParams,
+    pub feature_names: Vec<String>,
+    pub target_names: Vec<String>,
+    pub base_scores: Vec<f64>,
+    pub trees: Vec<Vec<Tree>>,
+}
+
+/// The training rows with each feature's values replaced by the index of their bin, stored by feature.
+struct BinnedFeatures {
+    /// The upper edge of every bin but the last, by feature.
+    thresholds: Vec<Vec<f64>>,
+    bins: Vec<Vec<u8>>,
+}
+
+impl BinnedFeatures {
+    fn new(rows: &[Vec<f64>], max_bin: usize) -> Self {
+        let width = rows.first().map_or(0, |row| row.len());
+        let max_bin = max_bin.clamp(2, MISSING_BIN as usize);
+
+        let mut thresholds = Vec::new();
+        let mut bins = Vec::new();
+        for feature in 0..width {
+            let mut values: Vec<f64> = rows.iter().map(|row| row[feature]).filter(|value| !value.is_nan()).collect();
+            values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            values.dedup();
+
+            // split the distinct values into equally sized groups
+            let feature_thresholds: Vec<f64> = if values.len() <= max_bin {
+                values.iter().take(values.len().saturating_sub(1)).copied().collect()
+            } else {
+                (1..max_bin).map(|i| values[i * values.len() / max_bin - 1]).collect()
+            };
+
+            bins.push(
+                rows.iter()
+                    .map(|row| {
+                        let value = row[feature];
+                        if value.is_nan() {
+                            MISSING_BIN
+                        } else {
+                            feature_thresholds.partition_point(|threshold| *threshold < value) as u8
+                        }
+                    })
+                    .collect(),
+            );
+            thresholds.push(feature_thresholds);
+        }
+
+        BinnedFeatures { thresholds, bins }
+    }
+}
+
+/// The sums of gradients and hessians of the rows in each bin of a feature.
+#[derive(Clone)]
+struct Histogram {
+    gradients: Vec<f64>,
+    hessians: Vec<f64>,
+    missing: (f64, f64),
+}
+
+struct SplitCandidate {
+    gain: f64,
+    feature: usize,
+    bin: usize,
+    missing_left: bool,
+}
+
+/// Grows one tree from the gradients of the current predictions.
+struct TreeBuilder<'a> {
+    params: &'a BoostingParams,
+    features: &'a BinnedFeatures,
+    gradients: &'a [f64],
+    hessians: &'a [f64],
+    columns: Vec<usize>,
+    nodes: Vec<Node>,
+}
+
+impl TreeBuilder<'_> {
+    fn score(&self, gradient: f64, hessian: f64) -> f64 {
+        gradient * gradient / (hessian + self.params.lambda)
+    }
+
+    fn leaf(&self, gradient: f64, hessian: f64) -> Node {
+        Node::Leaf {
+            value: -self.params.learning_rate * gradient / (hessian + self.params.lambda),
+        }
+    }
+
+    fn histogram(&self, feature: usize, rows: &[usize]) -> Histogram {
+        let bins = self.features.thresholds[feature].len() + 1;
+        let mut histogram = Histogram {
+            gradients: vec![0.0; bins],
+            hessians: vec![0.0; bins],
+            missing: (0.0, 0.0),
+        };
+        for &row in rows {
+            let bin = self.features.bins[feature][row];
+            if bin == MISSING_BIN {
+                histogram.missing.0 += self.gradients[row];
+                histogram.missing.1 += self.hessians[row];
+            } else {
+                histogram.gradients[bin as usize] += self.gradients[row];
+                histogram.hessians[bin as usize] += self.hessians[row];
+            }
+        }
+
+        histogram
+    }
+
+    fn best_split(&self, rows: &[usize], gradient: f64, hessian: f64) -> Option<SplitCandidate> {
+        let parent_score = self.score(gradient, hessian);
+
+        let mut best: Option<SplitCandidate> = None;
+        for &feature in &self.columns {
+            let histogram = self.histogram(feature, rows);
+            let (missing_gradient, missing_hessian) = histogram.missing;
+
+            let mut left_gradient = 0.0;
+            let mut left_hessian = 0.0;
+            for bin in 0..histogram.gradients.len() - 1 {
+                left_gradient += histogram.gradients[bin];
+                left_hessian += histogram.hessians[bin];
+
+                for missing_left in [false, true] {
+                    let (left_gradient, left_hessian) = if missing_left {
+                        (left_gradient + missing_gradient, left_hessian + missing_hessian)
+                    } else {
+                        (left_gradient, left_hessian)
+                    };
+                    let right_gradient = gradient - left_gradient;
+                    let right_hessian = hessian - left_hessian;
+                    if left_hessian < self.params.min_child_weight || right_hessian < self.params.min_child_weight {
+                        continue;
+                    }
+
+                    let gain = self.score(left_gradient, left_hessian) + self.score(right_gradient, right_hessian) - parent_score;
+                    if gain > 0.0 && best.as_ref().is_none_or(|best| gain > best.gain) {
+                        best = Some(SplitCandidate { gain, feature, bin, missing_left });
+                    }
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Grow the subtree of the given rows, returning the index of its root.
+    fn grow(&mut self, rows: Vec<usize>, depth: u8) -> usize {
+        let gradient: f64 = rows.iter().map(|row| self.gradients[*row]).sum();
+        let hessian: f64 = rows.iter().map(|row| self.hessians[*row]).sum();
+
+        let index = self.nodes.len();
+        self.nodes.push(self.leaf(gradient, hessian));
+        if depth >= self.params.max_depth {
+            return index;
+        }
+        let Some(split) = self.best_split(&rows, gradient, hessian) else {
+            return index;
+        };
+
+        let bins = &self.features.bins[split.feature];
+        let (left_rows, right_rows): (Vec<usize>, Vec<usize>) = rows.into_iter().partition(|row| {
+            let bin = bins[*row];
+            if bin == MISSING_BIN { split.missing_left } else { bin as usize <= split.bin }
+        });
+
+        let left = self.grow(left_rows, depth + 1);
+        let right = self.grow(right_rows, depth + 1);
+        self.nodes[index] = Node::Split {
+            feature: split.feature,
+            threshold: self.features.thresholds[split.feature][split.bin],
+            missing_left: split.missing_left,
+            left,
+            right,
+        };
+
+        index
+    }
+}
+
+/// The mean loss of raw scores, which is what early stopping watches.
+fn mean_loss(loss: Loss, targets: &[f64], scores: &[f64]) -> f64 {
+    let total: f64 = targets
+        .iter()
+        .zip(scores)
+        .map(|(y, score)| match loss {
+            Loss::SquaredError => (y - score).powi(2),
+            Loss::Poisson => 2.0 * (score.exp() - y * score),
+        })
+        .sum();
+
+    total / targets.len() as f64
+}
+
+impl GradientBoosting {
+    /// Fit an ensemble to each target of `train`, stopping early once the loss on `validation` stops improving and
+    /// keeping the trees up to its best round.
+    pub fn fit(train: &Dataset, validation: &Dataset, params: &BoostingParams) -> Result<Self, String> {
+        if train.is_empty() || validation.is_empty() {
+            return Err("Cannot fit a model to an empty dataset".to_string());
+        }
+
+        let features = BinnedFeatures::new(&train.features, params.max_bin);
+        let width = train.feature_names.len();
+        let mut rng = Rng::new(params.seed);
+
+        let mut base_scores = Vec::new();
+        let mut trees = Vec::new();
+        for target in 0..train.target_names.len() {
+            let targets = train.target(target);
+            let validation_targets = validation.target(target);
+
+            let mean = targets.iter().sum::<f64>() / targets.len() as f64;
+            let base_score = match params.loss {
+                Loss::SquaredError => mean,
+                Loss::Poisson if mean > 0.0 => mean.ln(),
+                Loss::Poisson => return Err("Poisson loss needs a positive mean target".to_string()),
+            };
+
+            let mut scores = vec![base_score; train.len()];
+            let mut validation_scores = vec![base_score; validation.len()];
+            let mut target_trees = Vec::new();
+            let mut best_loss = f64::INFINITY;
+            let mut best_round = 0;
+            for round in 0..params.num_boost_round {
+                let (gradients, hessians): (Vec<f64>, Vec<f64>) = targets
+                    .iter()
+                    .zip(&scores)
+                    .map(|(y, score)| match params.loss {
+                        Loss::SquaredError => (score - y, 1.0),
+                        Loss::Poisson => (score.exp() - y, score.exp()),
+                    })
+                    .unzip();
+
+                let mut rows: Vec<usize> = (0..train.len()).collect();
+                rng.shuffle(&mut rows);
+                rows.truncate(((train.len() as f64 * params.subsample).ceil() as usize).max(1));
+                let mut columns: Vec<usize> = (0..width).collect();
+                rng.shuffle(&mut columns);
+                columns.truncate(((width as f64 * params.colsample_bytree).ceil() as usize).max(1));
+
+                let mut builder = TreeBuilder {
+                    params,
+                    features: &features,
+                    gradients: &gradients,
+                    hessians: &hessians,
+                    columns,
+                    nodes: Vec::new(),
+                };
+                builder.grow(rows, 0);
+                let tree = Tree { nodes: builder.nodes };
+
+                let clamp = |score: f64| match params.loss {
+                    Loss::SquaredError => score,
+                    Loss::Poisson => score.clamp(-MAX_RAW_SCORE, MAX_RAW_SCORE),
+                };
+                for (score, row) in scores.iter_mut().zip(&train.features) {
+                    *score = clamp(*score + tree.predict(row));
+                }
+                for (score, row) in validation_scores.iter_mut().zip(&validation.features) {
+                    *score = clamp(*score + tree.predict(row));
+                }
+                target_trees.push(tree);
+
+                let loss = mean_loss(params.loss, &validation_targets, &validation_scores);
+                if loss < best_loss {
+                    best_loss = loss;
+                    best_round = round;
+                } else if round - best_round >= params.early_stopping_rounds {
+                    break;
+                }
+            }
+            target_trees.truncate(best_round + 1);
+
+            base_scores.push(base_score);
+            trees.push(target_trees);
+        }
+
+        Ok(GradientBoosting {
+            params: params.clone(),
+            feature_names: train.feature_names.clone(),
+            target_names: train.target_names.clone(),
+            base_scores,
+            trees,
+        })
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        load_json(path)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        save_json(self, path)
+    }
+}
+
+impl Regressor for GradientBoosting {
+    fn feature_names(&self) -> &[String] {
+        &self.feature_names
+    }
+
+    fn predict(&self, row: &[f64]) -> Vec<f64> {
+        self.base_scores
+            .iter()
+            .zip(&self.trees)
+            .map(|(base_score, trees)| {
+                let score = base_score + trees.iter().map(|tree| tree.predict(row)).sum::<f64>();
+                match self.params.loss {
+                    Loss::SquaredError => score,
+                    Loss::Poisson => score.clamp(-MAX_RAW_SCORE, MAX_RAW_SCORE).exp(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataset(xs: &[f64], ys: &[f64]) -> Dataset {
+        Dataset {
+            feature_names: vec!["x".to_string()],
+            target_names: vec!["y".to_string()],
+            features: xs.iter().map(|x| vec![*x]).collect(),
+            targets: ys.iter().map(|y| vec![*y]).collect(),
+        }
+    }
+
+    /// One exact tree: every row used, no shrinkage or penalty.
+    fn single_split() -> BoostingParams {
+        BoostingParams {
+            learning_rate: 1.0,
+            max_depth: 1,
+            num_boost_round: 1,
+            subsample: 1.0,
+            colsample_bytree: 1.0,
+            min_child_weight: 0.0,
+            lambda: 0.0,
+            max_bin: 4,
+            ..BoostingParams::default()
+        }
+    }
+
+    #[test]
+    fn bins_and_thresholds_agree() {
+        let rows: Vec<Vec<f64>> = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, f64::NAN].iter().map(|x| vec![*x]).collect();
+        let features = BinnedFeatures::new(&rows, 4);
+
+        // 8 distinct values in 4 bins of 2
+        assert_eq!(features.thresholds[0], vec![2.0, 4.0, 6.0]);
+        assert_eq!(features.bins[0], vec![0, 0, 1, 1, 2, 2, 3, 3, MISSING_BIN]);
+
+        // a split after a bin sends the same rows left as its threshold does
+        for (bin, threshold) in features.thresholds[0].iter().enumerate() {
+            for (row, row_bin) in rows.iter().zip(&features.bins[0]).take(8) {
+                assert_eq!(*row_bin as usize <= bin, row[0] <= *threshold, "{} in bin {}", row[0], row_bin);
+            }
+        }
+    }
+
+    #[test]
+    fn split_threshold_is_the_bin_edge() {
+        let data = dataset(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], &[0.0, 0.0, 0.0, 0.0, 10.0, 10.0, 10.0, 10.0]);
+        let model = GradientBoosting::fit(&data, &data, &single_split()).unwrap();
+
+        let Node::Split { threshold, .. } = model.trees[0][0].nodes[0] else {
+            panic!("the root didn't split");
+        };
+        assert_eq!(threshold, 4.0);
+        assert_eq!(model.predict(&[4.0]), vec![0.0]);
+        assert_eq!(model.predict(&[4.5]), vec![10.0]);
+    }
+
+    #[test]
+    fn missing_values_go_with_the_rows_they_resemble() {
+        let xs = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, f64::NAN, f64::NAN];
+        for (missing_target, missing_left) in [(10.0, false), (0.0, true)] {
+            let ys = [0.0, 0.0, 0.0, 0.0, 10.0, 10.0, 10.0, 10.0, missing_target, missing_target];
+            let data = dataset(&xs, &ys);
+            let model = GradientBoosting::fit(&data, &data, &single_split()).unwrap();
+
+            let Node::Split { missing_left: learned, .. } = model.trees[0][0].nodes[0] else {
+                panic!("the root didn't split");
+            };
+            assert_eq!(learned, missing_left);
+            assert_eq!(model.predict(&[f64::NAN]), vec![missing_target]);
+        }
+    }
+
+    #[test]
+    fn early_stopping_keeps_the_best_round() {
+        let xs: Vec<f64> = (0..20).map(|x| x as f64).collect();
+        let ys = xs.clone();
+        let params = BoostingParams {
+            learning_rate: 0.3,
+            num_boost_round: 30,
+            early_stopping_rounds: 3,
+            subsample: 1.0,
+            colsample_bytree: 1.0,
+            min_child_weight: 1.0,
+            ..BoostingParams::default()
+        };
+
+        // every round improves the training rows, so they're all kept
+        let train = dataset(&xs, &ys);
+        let model = GradientBoosting::fit(&train, &train, &params).unwrap();
+        assert_eq!(model.trees[0].len(), 30);
+
+        // every round makes these rows worse, so early stopping keeps only the first tree
+        let opposite: Vec<f64> = ys.iter().map(|y| -y).collect();
+        let model = GradientBoosting::fit(&train, &dataset(&xs, &opposite), &params).unwrap();
+        assert_eq!(model.trees[0].len(), 1);
+    }
+
+    #[test]
+    fn fitting_separable_data_reduces_the_loss() {
+        let xs: Vec<f64> = (0..40).map(|x| x as f64).collect();
+        let ys: Vec<f64> = xs.iter().map(|x| if *x < 20.0 { 2.0 } else { 8.0 }).collect();
+        let data = dataset(&xs, &ys);
+        for loss in [Loss::SquaredError, Loss::Poisson] {
+            let params = BoostingParams { loss, learning_rate: 0.3, num_boost_round: 100, ..BoostingParams::default() };
+            let model = GradientBoosting::fit(&data, &data, &params).unwrap();
+
+            let predictions: Vec<f64> = xs.iter().map(|x| model.predict(&[*x])[0]).collect();
+            let error = predictions.iter().zip(&ys).map(|(prediction, y)| (prediction - y).powi(2)).sum::<f64>() / ys.len() as f64;
+            // the mean alone is off by 3 everywhere
+            assert!(error < 0.1, "{:?} mean squared error {}", loss, error);
+        }
+    }
+}
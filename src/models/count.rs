@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::linescore::DEFAULT_SCHEDULED_INNINGS;
+use crate::markov::MAX_RUNS;
+use crate::model::Game;
+
+/// The number of league-average games each team's strengths are regressed toward.
+const PRIOR_GAMES: f64 = 10.0;
+
+/// The maximum number of passes when fitting the team strengths.
+const MAX_ITERATIONS: usize = 200;
+
+/// The largest change in any strength below which the fit has converged.
+const TOLERANCE: f64 = 1e-9;
+
+/// A count model of runs scored: each team's expected runs are a league baseline, times a home-field factor for the
+/// home team, times the batting team's offense and the fielding team's defense (runs allowed) multipliers, scaled to
+/// the scheduled innings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountModel {
+    /// Runs per team per nine innings for average teams on neutral ground.
+    pub baseline: f64,
+    pub home_advantage: f64,
+    pub offense: HashMap<u8, f64>,
+    pub defense: HashMap<u8, f64>,
+    /// The size parameter of the negative binomial distribution of each team's runs (its variance is
+    /// `mean + mean² / dispersion`), or `None` if runs aren't overdispersed and are Poisson.
+    pub dispersion: Option<f64>,
+    /// The covariance of the home and away runs in the bivariate Poisson model.
+    pub covariance: f64,
+}
+
+#[derive(Clone, Copy)]
+enum Factor {
+    Offense,
+    Defense,
+    HomeAdvantage,
+    Baseline,
+}
+
+/// The share of nine innings a game is scheduled for.
+fn exposure(game: &Game) -> f64 {
+    game.setting.scheduled_innings.unwrap_or(DEFAULT_SCHEDULED_INNINGS) as f64 / 9.0
+}
+
+/// The probabilities of 0 to [`MAX_RUNS`] runs under a Poisson distribution.
+fn poisson_probabilities(mean: f64) -> Vec<f64> {
+    let mut probabilities = vec![(-mean).exp()];
+    for k in 1..=MAX_RUNS {
+        probabilities.push(probabilities[k - 1] * mean / k as f64);
+    }
+
+    probabilities
+}
+
+/// The probabilities of 0 to [`MAX_RUNS`] runs under a negative binomial distribution with the given mean and size.
+fn negative_binomial_probabilities(mean: f64, size: f64) -> Vec<f64> {
+    let p = size / (size + mean);
+    let mut probabilities = vec![p.powf(size)];
+    for k in 1..=MAX_RUNS {
+        probabilities.push(probabilities[k - 1] * (k as f64 - 1.0 + size) / k as f64 * (1.0 - p));
+    }
+
+    probabilities
+}
+
+/// Scale a score grid so its probabilities add up to one, dropping the mass beyond [`MAX_RUNS`].
+fn normalized(mut grid: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    let total: f64 = grid.iter().flatten().sum();
+    for probability in grid.iter_mut().flatten() {
+        *probability /= total;
+    }
+
+    grid
+}
+
+/// The probability that the home team wins according to a score grid indexed by `[home runs][away runs]`. Ties are
+/// settled in extra innings, which are taken to be a coin flip.
+pub fn home_win_probability(grid: &[Vec<f64>]) -> f64 {
+    let mut probability = 0.0;
+    for (home_runs, row) in grid.iter().enumerate() {
+        for (away_runs, cell) in row.iter().enumerate() {
+            if home_runs > away_runs {
+                probability += cell;
+            } else if home_runs == away_runs {
+                probability += cell / 2.0;
+            }
+        }
+    }
+
+    probability
+}
+
+impl CountModel {
+    /// Fit the model to the given games by maximum likelihood of the Poisson model (whose means the negative binomial
+    /// shares), with each team's strengths regressed toward average, then estimate the dispersion and covariance
+    /// from the residuals.
+    pub fn fit<'a>(games: impl IntoIterator<Item = &'a Game>) -> Result<Self, String> {
+        let games: Vec<&Game> = games.into_iter().collect();
+        if games.is_empty() {
+            return Err("Cannot fit a count model to no games".to_string());
+        }
+
+        // with no runs on either side, the factors of that side are 0 / 0
+        let home_runs: f64 = games.iter().map(|game| game.result.home_team_score as f64).sum();
+        let away_runs: f64 = games.iter().map(|game| game.result.away_team_score as f64).sum();
+        if home_runs == 0.0 || away_runs == 0.0 {
+            return Err("Cannot fit a count model to games where a side never scored".to_string());
+        }
+
+        let total_runs = home_runs + away_runs;
+        let total_exposure: f64 = games.iter().map(|game| 2.0 * exposure(game)).sum();
+        let mut model = CountModel {
+            baseline: total_runs / total_exposure,
+            home_advantage: 1.0,
+            offense: HashMap::new(),
+            defense: HashMap::new(),
+            dispersion: None,
+            covariance: 0.0,
+        };
+        for game in &games {
+            for team_id in [game.home_team.team_id, game.away_team.team_id] {
+                model.offense.insert(team_id, 1.0);
+                model.defense.insert(team_id, 1.0);
+            }
+        }
+
+        for _ in 0..MAX_ITERATIONS {
+            let mut change: f64 = 0.0;
+            for factor in [Factor::Offense, Factor::Defense, Factor::HomeAdvantage, Factor::Baseline] {
+                change = change.max(model.refit(&games, factor));
+            }
+            if change < TOLERANCE {
+                break;
+            }
+        }
+
+        // method of moments, from var = mean + mean² / dispersion and cov = covariance
+        let mut squared_means = 0.0;
+        let mut excess_variance = 0.0;
+        let mut covariance = 0.0;
+        for game in &games {
+            let (home_mean, away_mean) = model.expected_runs(game);
+            let home_residual = game.result.home_team_score as f64 - home_mean;
+            let away_residual = game.result.away_team_score as f64 - away_mean;
+
+            squared_means += home_mean.powi(2) + away_mean.powi(2);
+            excess_variance += home_residual.powi(2) - home_mean + away_residual.powi(2) - away_mean;
+            covariance += home_residual * away_residual;
+        }
+        model.dispersion = (excess_variance > 0.0).then(|| squared_means / excess_variance);
+        model.covariance = (covariance / games.len() as f64).max(0.0);
+
+        Ok(model)
+    }
+
+    /// Refit one block of factors with the others held fixed, which for the Poisson likelihood sets each factor to the
+    /// ratio of the runs it accounts for to the runs expected without it. Returns the largest change in a factor.
+    fn refit(&mut self, games: &[&Game], factor: Factor) -> f64 {
+        // runs and expected runs without the factor, by team (or under 0 for the league-wide factors)
+        let mut sums: HashMap<u8, (f64, f64)> = HashMap::new();
+        for game in games {
+            let (home_mean, away_mean) = self.expected_runs(game);
+            let home = game.home_team.team_id;
+            let away = game.away_team.team_id;
+            let home_runs = game.result.home_team_score as f64;
+            let away_runs = game.result.away_team_score as f64;
+
+            let contributions = match factor {
+                Factor::Offense => [(home, home_runs, home_mean / self.offense[&home]), (away, away_runs, away_mean / self.offense[&away])],
+                Factor::Defense => [(away, home_runs, home_mean / self.defense[&away]), (home, away_runs, away_mean / self.defense[&home])],
+                Factor::HomeAdvantage => [(0, home_runs, home_mean / self.home_advantage), (0, 0.0, 0.0)],
+                Factor::Baseline => [(0, home_runs, home_mean / self.baseline), (0, away_runs, away_mean / self.baseline)],
+            };
+            for (key, runs, expected) in contributions {
+                let sum = sums.entry(key).or_insert((0.0, 0.0));
+                sum.0 += runs;
+                sum.1 += expected;
+            }
+        }
+
+        let prior = PRIOR_GAMES * self.baseline;
+        let mut change: f64 = 0.0;
+        for (key, (runs, expected)) in sums {
+            let (value, new_value) = match factor {
+                Factor::Offense => (self.offense.get_mut(&key).unwrap(), (runs + prior) / (expected + prior)),
+                Factor::Defense => (self.defense.get_mut(&key).unwrap(), (runs + prior) / (expected + prior)),
+                Factor::HomeAdvantage => (&mut self.home_advantage, runs / expected),
+                Factor::Baseline => (&mut self.baseline, runs / expected),
+            };
+            change = change.max((new_value - *value).abs());
+            *value = new_value;
+        }
+
+        change
+    }
+
+    /// A team's strength factor, or an average team's if it hasn't been seen.
+    fn team_factor(factors: &HashMap<u8, f64>, team_id: u8) -> f64 {
+        factors.get(&team_id).copied().unwrap_or(1.0)
+    }
+
+    /// The expected home and away runs of a game.
+    pub fn expected_runs(&self, game: &Game) -> (f64, f64) {
+        let home = game.home_team.team_id;
+        let away = game.away_team.team_id;
+        let scale = self.baseline * exposure(game);
+
+        (
+            scale * self.home_advantage * CountModel::team_factor(&self.offense, home) * CountModel::team_factor(&self.defense, away),
+            scale * CountModel::team_factor(&self.offense, away) * CountModel::team_factor(&self.defense, home),
+        )
+    }
+
+    /// The probability of each score of a game, indexed by `[home runs][away runs]`, with each team's runs
+    /// independently negative binomial.
+    pub fn negative_binomial_grid(&self, game: &Game) -> Vec<Vec<f64>> {
+        let (home_mean, away_mean) = self.expected_runs(game);
+        let margin = |mean: f64| match self.dispersion {
+            Some(dispersion) => negative_binomial_probabilities(mean, dispersion),
+            None => poisson_probabilities(mean),
+        };
+
+        let home = margin(home_mean);
+        let away = margin(away_mean);
+        normalized(home.iter().map(|home| away.iter().map(|away| home * away).collect()).collect())
+    }
+
+    /// The probability of each score of a game, indexed by `[home runs][away runs]`, under a bivariate Poisson model:
+    /// both teams' runs share a Poisson component whose mean is the covariance.
+    pub fn bivariate_poisson_grid(&self, game: &Game) -> Vec<Vec<f64>> {
+        let (home_mean, away_mean) = self.expected_runs(game);
+        let shared_mean = self.covariance.min(0.99 * home_mean.min(away_mean));
+
+        let home = poisson_probabilities(home_mean - shared_mean);
+        let away = poisson_probabilities(away_mean - shared_mean);
+        let shared = poisson_probabilities(shared_mean);
+        let grid = (0..=MAX_RUNS)
+            .map(|home_runs| {
+                (0..=MAX_RUNS)
+                    .map(|away_runs| (0..=home_runs.min(away_runs)).map(|k| home[home_runs - k] * away[away_runs - k] * shared[k]).sum())
+                    .collect()
+            })
+            .collect();
+
+        normalized(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A model where every team is average, so the home team expects `4.5 * home_advantage` runs and the away team
+    /// 4.5.
+    fn model(home_advantage: f64, dispersion: Option<f64>, covariance: f64) -> CountModel {
+        CountModel {
+            baseline: 4.5,
+            home_advantage,
+            offense: HashMap::new(),
+            defense: HashMap::new(),
+            dispersion,
+            covariance,
+        }
+    }
+
+    /// The means and variances of the home and away runs, and their covariance.
+    fn moments(grid: &[Vec<f64>]) -> ((f64, f64), (f64, f64), f64) {
+        let mut sums = [0.0; 5];
+        for (home_runs, row) in grid.iter().enumerate() {
+            for (away_runs, probability) in row.iter().enumerate() {
+                let (home_runs, away_runs) = (home_runs as f64, away_runs as f64);
+                sums[0] += probability * home_runs;
+                sums[1] += probability * away_runs;
+                sums[2] += probability * home_runs * home_runs;
+                sums[3] += probability * away_runs * away_runs;
+                sums[4] += probability * home_runs * away_runs;
+            }
+        }
+
+        (
+            (sums[0], sums[2] - sums[0].powi(2)),
+            (sums[1], sums[3] - sums[1].powi(2)),
+            sums[4] - sums[0] * sums[1],
+        )
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-4, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn grids_sum_to_one() {
+        let game = Game::with_score("2024-05-01", 1, 2, 0, 0);
+        // the second has most of its mass beyond MAX_RUNS, which is dropped
+        for model in [model(1.1, Some(5.0), 0.5), model(10.0, Some(0.5), 0.5)] {
+            for grid in [model.negative_binomial_grid(&game), model.bivariate_poisson_grid(&game)] {
+                assert_eq!(grid.len(), MAX_RUNS + 1);
+                assert!(grid.iter().flatten().all(|probability| probability.is_finite() && *probability >= 0.0));
+                assert_close(grid.iter().flatten().sum(), 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn negative_binomial_moments() {
+        let game = Game::with_score("2024-05-01", 1, 2, 0, 0);
+
+        let ((home_mean, home_variance), (away_mean, away_variance), covariance) = moments(&model(1.1, Some(20.0), 0.0).negative_binomial_grid(&game));
+        assert_close(home_mean, 4.95);
+        assert_close(home_variance, 4.95 + 4.95 * 4.95 / 20.0);
+        assert_close(away_mean, 4.5);
+        assert_close(away_variance, 4.5 + 4.5 * 4.5 / 20.0);
+        assert_close(covariance, 0.0);
+
+        // without overdispersion the runs are Poisson
+        let ((home_mean, home_variance), _, _) = moments(&model(1.1, None, 0.0).negative_binomial_grid(&game));
+        assert_close(home_mean, 4.95);
+        assert_close(home_variance, 4.95);
+    }
+
+    #[test]
+    fn bivariate_poisson_moments() {
+        let game = Game::with_score("2024-05-01", 1, 2, 0, 0);
+
+        let ((home_mean, home_variance), (away_mean, away_variance), covariance) = moments(&model(1.1, None, 0.8).bivariate_poisson_grid(&game));
+        assert_close(home_mean, 4.95);
+        assert_close(home_variance, 4.95);
+        assert_close(away_mean, 4.5);
+        assert_close(away_variance, 4.5);
+        // the shared component is the covariance
+        assert_close(covariance, 0.8);
+    }
+
+    #[test]
+    fn fitting_needs_runs_on_both_sides() {
+        assert!(CountModel::fit(std::iter::empty()).is_err());
+
+        let shutouts = [Game::with_score("2024-05-01", 1, 2, 0, 0), Game::with_score("2024-05-02", 2, 1, 3, 0)];
+        assert!(CountModel::fit(&shutouts).is_err());
+
+        let games = [Game::with_score("2024-05-01", 1, 2, 5, 3), Game::with_score("2024-05-02", 2, 1, 2, 4)];
+        let model = CountModel::fit(&games).unwrap();
+        assert!(model.baseline.is_finite() && model.home_advantage.is_finite());
+        assert!(model.offense.values().chain(model.defense.values()).all(|factor| factor.is_finite()));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::model::Game;
+use crate::models::features::{feature_row, feature_value};
+use crate::models::TARGETS;
+use crate::rng::Rng;
+
+/// A feature matrix and its targets, one row per game.
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    pub feature_names: Vec<String>,
+    pub target_names: Vec<String>,
+    pub features: Vec<Vec<f64>>,
+    pub targets: Vec<Vec<f64>>,
+}
+
+/// Parse a cell written by `training/convert_data.py`. Empty cells are missing values.
+fn parse_cell(cell: &str) -> f64 {
+    match cell {
+        "True" => 1.0,
+        "False" => 0.0,
+        _ => cell.parse().unwrap_or(f64::NAN),
+    }
+}
+
+impl Dataset {
+    /// Load the feature matrix exported by `training/convert_data.py` (e.g. `data/data.csv`). Every `result.*`
+    /// column is an outcome of the game, so only [`TARGETS`] are kept from them, and games without both are skipped.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let csv = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read file {}: {}", path, e))?;
+        let mut lines = csv.lines();
+
+        let header: Vec<&str> = lines.next().ok_or(format!("File {} is empty", path))?.split(',').collect();
+        let feature_columns: Vec<usize> = (0..header.len()).filter(|i| !header[*i].starts_with("result.")).collect();
+        let target_columns = TARGETS
+            .iter()
+            .map(|target| header.iter().position(|column| column == target).ok_or(format!("File {} has no {} column", path, target)))
+            .collect::<Result<Vec<usize>, String>>()?;
+
+        let mut features = Vec::new();
+        let mut targets = Vec::new();
+        for (i, line) in lines.enumerate() {
+            let cells: Vec<&str> = line.split(',').collect();
+            if cells.len() != header.len() {
+                return Err(format!("Row {} of {} has {} cells, expected {}", i + 1, path, cells.len(), header.len()));
+            }
+
+            let target: Vec<f64> = target_columns.iter().map(|column| parse_cell(cells[*column])).collect();
+            if target.iter().any(|value| value.is_nan()) {
+                continue;
+            }
+            features.push(feature_columns.iter().map(|column| parse_cell(cells[*column])).collect());
+            targets.push(target);
+        }
+
+        Ok(Dataset {
+            feature_names: feature_columns.iter().map(|column| header[*column].to_string()).collect(),
+            target_names: TARGETS.iter().map(|target| target.to_string()).collect(),
+            features,
+            targets,
+        })
+    }
+
+    /// Build a dataset of the given features directly from stored games.
+    pub fn from_games<'a>(games: impl IntoIterator<Item = &'a Game>, feature_names: &[String]) -> Self {
+        let mut features = Vec::new();
+        let mut targets = Vec::new();
+        for game in games {
+            let value = serde_json::to_value(game).unwrap();
+            features.push(feature_row(game, feature_names));
+            targets.push(TARGETS.iter().map(|target| feature_value(&value, target)).collect());
+        }
+
+        Dataset {
+            feature_names: feature_names.to_vec(),
+            target_names: TARGETS.iter().map(|target| target.to_string()).collect(),
+            features,
+            targets,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    /// The values of one target, by its index in `target_names`.
+    pub fn target(&self, index: usize) -> Vec<f64> {
+        self.targets.iter().map(|target| target[index]).collect()
+    }
+
+    pub fn subset(&self, indices: &[usize]) -> Self {
+        Dataset {
+            feature_names: self.feature_names.clone(),
+            target_names: self.target_names.clone(),
+            features: indices.iter().map(|i| self.features[*i].clone()).collect(),
+            targets: indices.iter().map(|i| self.targets[*i].clone()).collect(),
+        }
+    }
+
+    /// Randomly split the rows into a training and a test set, like `train_test_split` in `training/train.py`.
+    pub fn split(&self, test_fraction: f64, seed: u64) -> (Self, Self) {
+        let mut indices: Vec<usize> = (0..self.len()).collect();
+        Rng::new(seed).shuffle(&mut indices);
+
+        let test_size = (self.len() as f64 * test_fraction).ceil() as usize;
+        (self.subset(&indices[test_size..]), self.subset(&indices[..test_size]))
+    }
+}
@@ -0,0 +1,56 @@
+use serde_json::Value;
+
+use crate::model::Game;
+
+/// The integer code of a categorical value, by the last part of its feature key, matching `CATEGORIES` in
+/// `training/convert_data.py`.
+fn category_code(key: &str, value: &str) -> Option<f64> {
+    let categories: &[&str] = match key {
+        "hand" => &["left", "right", "switch"],
+        "roof_type" => &["open", "retractable", "dome"],
+        "surface" => &["grass", "artificial_turf"],
+        "day_night" => &["day", "night"],
+        "condition" => &["clear", "sunny", "partly_cloudy", "cloudy", "overcast", "drizzle", "rain", "snow", "roof_closed", "dome"],
+        "wind_direction" => &[
+            "calm",
+            "varies",
+            "out_to_left",
+            "out_to_center",
+            "out_to_right",
+            "in_from_left",
+            "in_from_center",
+            "in_from_right",
+            "left_to_right",
+            "right_to_left",
+        ],
+        "double_header" => &["no", "traditional", "split"],
+        _ => &[],
+    };
+
+    categories.iter().position(|category| *category == value).map(|code| code as f64)
+}
+
+/// Get the value of a dotted feature key (e.g. `home_team.batting_stats.avg`) from a serialized game, the same way
+/// `training/convert_data.py` does. Missing and undefined values are NaN.
+pub fn feature_value(game: &Value, feature_key: &str) -> f64 {
+    let mut value = game;
+    for key in feature_key.split('.') {
+        match value.get(key) {
+            Some(inner) => value = inner,
+            None => return f64::NAN,
+        }
+    }
+
+    match value {
+        Value::Number(number) => number.as_f64().unwrap_or(f64::NAN),
+        Value::Bool(flag) => *flag as u8 as f64,
+        Value::String(category) => category_code(feature_key.rsplit('.').next().unwrap(), category).unwrap_or(f64::NAN),
+        _ => f64::NAN,
+    }
+}
+
+/// Get the values of the given features from a game, in order.
+pub fn feature_row(game: &Game, feature_names: &[String]) -> Vec<f64> {
+    let game = serde_json::to_value(game).unwrap();
+    feature_names.iter().map(|feature_key| feature_value(&game, feature_key)).collect()
+}
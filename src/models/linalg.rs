@@ -0,0 +1,88 @@
+/// Solve `a x = b` for a symmetric positive definite `n`×`n` matrix `a`, stored row-major, by Cholesky decomposition.
+pub fn cholesky_solve(a: &[f64], b: &[f64]) -> Result<Vec<f64>, String> {
+    let n = b.len();
+
+    // a = l lᵀ, with l lower triangular
+    let mut l = vec![0.0; n * n];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| l[i * n + k] * l[j * n + k]).sum();
+            if i == j {
+                let diagonal = a[i * n + i] - sum;
+                if diagonal <= 0.0 || !diagonal.is_finite() {
+                    return Err(format!("Matrix is not positive definite (pivot {} is {})", i, diagonal));
+                }
+                l[i * n + i] = diagonal.sqrt();
+            } else {
+                l[i * n + j] = (a[i * n + j] - sum) / l[j * n + j];
+            }
+        }
+    }
+
+    // l y = b
+    let mut y = vec![0.0; n];
+    for i in 0..n {
+        let sum: f64 = (0..i).map(|k| l[i * n + k] * y[k]).sum();
+        y[i] = (b[i] - sum) / l[i * n + i];
+    }
+
+    // lᵀ x = y
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let sum: f64 = (i + 1..n).map(|k| l[k * n + i] * x[k]).sum();
+        x[i] = (y[i] - sum) / l[i * n + i];
+    }
+
+    Ok(x)
+}
+
+/// Compute `xᵀ w x` and `xᵀ w y` for the rows `x`, weights `w` and targets `y`, with a leading intercept column of ones
+/// added to every row.
+pub fn weighted_normal_equations(rows: &[Vec<f64>], weights: &[f64], targets: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let n = rows.first().map_or(0, |row| row.len()) + 1;
+
+    let mut xtx = vec![0.0; n * n];
+    let mut xty = vec![0.0; n];
+    let mut x = vec![1.0; n];
+    for ((row, weight), target) in rows.iter().zip(weights).zip(targets) {
+        x[1..].copy_from_slice(row);
+        for i in 0..n {
+            let wx = weight * x[i];
+            xty[i] += wx * target;
+            for j in 0..=i {
+                xtx[i * n + j] += wx * x[j];
+            }
+        }
+    }
+
+    // only the lower triangle was accumulated
+    for i in 0..n {
+        for j in 0..i {
+            xtx[j * n + i] = xtx[i * n + j];
+        }
+    }
+
+    (xtx, xty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cholesky_solve_known_system() {
+        // a = l lᵀ with l = [[2, 0, 0], [6, 1, 0], [-8, 5, 3]]
+        let a = [4.0, 12.0, -16.0, 12.0, 37.0, -43.0, -16.0, -43.0, 98.0];
+        let b = [-20.0, -43.0, 192.0];
+
+        let x = cholesky_solve(&a, &b).unwrap();
+        for (x, expected) in x.iter().zip([1.0, 2.0, 3.0]) {
+            assert!((x - expected).abs() < 1e-10, "{} != {}", x, expected);
+        }
+    }
+
+    #[test]
+    fn cholesky_solve_rejects_indefinite_matrix() {
+        assert!(cholesky_solve(&[1.0, 2.0, 2.0, 1.0], &[1.0, 1.0]).is_err());
+    }
+}
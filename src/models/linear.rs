@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::dataset::Dataset;
+use crate::models::linalg::{cholesky_solve, weighted_normal_equations};
+use crate::models::scaler::StandardScaler;
+use crate::models::{load_json, save_json, Regressor};
+
+/// Added to the penalty of every coefficient, so that constant and collinear features don't make the system singular.
+const JITTER: f64 = 1e-6;
+
+/// The maximum number of iteratively reweighted least squares steps when fitting a Poisson model.
+const MAX_ITERATIONS: usize = 50;
+
+/// The relative change in deviance below which a Poisson fit has converged.
+const TOLERANCE: f64 = 1e-8;
+
+/// The bound on the linear predictor of a Poisson model, so a bad step can't overflow `exp`.
+const MAX_LINEAR_PREDICTOR: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Link {
+    /// Linear (ridge) regression.
+    Identity,
+    /// Poisson regression.
+    Log,
+}
+
+/// A generalized linear model over standardized features, with one set of coefficients per target. Missing feature
+/// values are replaced with the training mean.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinearModel {
+    pub link: Link,
+    /// The L2 penalty on the coefficients (not the intercept).
+    pub alpha: f64,
+    pub feature_names: Vec<String>,
+    pub target_names: Vec<String>,
+    pub scaler: StandardScaler,
+    pub intercepts: Vec<f64>,
+    pub coefficients: Vec<Vec<f64>>,
+}
+
+/// Solve the penalized normal equations, returning the intercept and coefficients.
+fn solve(rows: &[Vec<f64>], weights: &[f64], targets: &[f64], alpha: f64) -> Result<(f64, Vec<f64>), String> {
+    let (mut xtx, xty) = weighted_normal_equations(rows, weights, targets);
+    let n = xty.len();
+    for i in 1..n {
+        xtx[i * n + i] += alpha + JITTER;
+    }
+
+    let mut solution = cholesky_solve(&xtx, &xty)?;
+    let coefficients = solution.split_off(1);
+    Ok((solution[0], coefficients))
+}
+
+fn linear_predictor(row: &[f64], intercept: f64, coefficients: &[f64]) -> f64 {
+    intercept + row.iter().zip(coefficients).map(|(x, b)| x * b).sum::<f64>()
+}
+
+fn poisson_deviance(targets: &[f64], means: &[f64]) -> f64 {
+    2.0 * targets
+        .iter()
+        .zip(means)
+        .map(|(y, mu)| if *y > 0.0 { y * (y / mu).ln() - (y - mu) } else { mu - y })
+        .sum::<f64>()
+}
+
+/// Fit a Poisson regression by iteratively reweighted least squares.
+fn fit_poisson_target(rows: &[Vec<f64>], targets: &[f64], alpha: f64) -> Result<(f64, Vec<f64>), String> {
+    let mean = targets.iter().sum::<f64>() / targets.len() as f64;
+    if mean <= 0.0 {
+        return Err("Poisson regression needs a positive mean target".to_string());
+    }
+
+    let mut intercept = mean.ln();
+    let mut coefficients = vec![0.0; rows.first().map_or(0, |row| row.len())];
+    let mut deviance = f64::INFINITY;
+    for _ in 0..MAX_ITERATIONS {
+        let eta: Vec<f64> = rows
+            .iter()
+            .map(|row| linear_predictor(row, intercept, &coefficients).clamp(-MAX_LINEAR_PREDICTOR, MAX_LINEAR_PREDICTOR))
+            .collect();
+        let means: Vec<f64> = eta.iter().map(|eta| eta.exp()).collect();
+
+        let new_deviance = poisson_deviance(targets, &means);
+        if (deviance - new_deviance).abs() <= TOLERANCE * new_deviance {
+            break;
+        }
+        deviance = new_deviance;
+
+        let working_targets: Vec<f64> = eta.iter().zip(&means).zip(targets).map(|((eta, mu), y)| eta + (y - mu) / mu).collect();
+        (intercept, coefficients) = solve(rows, &means, &working_targets, alpha)?;
+    }
+
+    Ok((intercept, coefficients))
+}
+
+impl LinearModel {
+    fn fit(dataset: &Dataset, link: Link, alpha: f64) -> Result<Self, String> {
+        if dataset.is_empty() {
+            return Err("Cannot fit a model to an empty dataset".to_string());
+        }
+
+        let scaler = StandardScaler::fit(&dataset.features);
+        let rows: Vec<Vec<f64>> = dataset.features.iter().map(|row| scaler.transform_filled(row)).collect();
+        let weights = vec![1.0; rows.len()];
+
+        let mut intercepts = Vec::new();
+        let mut coefficients = Vec::new();
+        for target in 0..dataset.target_names.len() {
+            let targets = dataset.target(target);
+            let (intercept, target_coefficients) = match link {
+                Link::Identity => solve(&rows, &weights, &targets, alpha)?,
+                Link::Log => fit_poisson_target(&rows, &targets, alpha)?,
+            };
+            intercepts.push(intercept);
+            coefficients.push(target_coefficients);
+        }
+
+        Ok(LinearModel {
+            link,
+            alpha,
+            feature_names: dataset.feature_names.clone(),
+            target_names: dataset.target_names.clone(),
+            scaler,
+            intercepts,
+            coefficients,
+        })
+    }
+
+    /// Fit a ridge regression (a linear regression, with `alpha` of 0) to each target.
+    pub fn fit_ridge(dataset: &Dataset, alpha: f64) -> Result<Self, String> {
+        LinearModel::fit(dataset, Link::Identity, alpha)
+    }
+
+    /// Fit a Poisson regression with a log link to each target.
+    pub fn fit_poisson(dataset: &Dataset, alpha: f64) -> Result<Self, String> {
+        LinearModel::fit(dataset, Link::Log, alpha)
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        load_json(path)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        save_json(self, path)
+    }
+}
+
+impl Regressor for LinearModel {
+    fn feature_names(&self) -> &[String] {
+        &self.feature_names
+    }
+
+    fn predict(&self, row: &[f64]) -> Vec<f64> {
+        let row = self.scaler.transform_filled(row);
+        self.intercepts
+            .iter()
+            .zip(&self.coefficients)
+            .map(|(intercept, coefficients)| {
+                let eta = linear_predictor(&row, *intercept, coefficients);
+                match self.link {
+                    Link::Identity => eta,
+                    Link::Log => eta.clamp(-MAX_LINEAR_PREDICTOR, MAX_LINEAR_PREDICTOR).exp(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poisson_recovers_known_coefficients() {
+        let mut rows = Vec::new();
+        for i in 0..11 {
+            for j in 0..11 {
+                rows.push(vec![i as f64 / 5.0 - 1.0, j as f64 / 5.0 - 1.0]);
+            }
+        }
+        // the targets are the means themselves, so the fit is exact
+        let targets: Vec<f64> = rows.iter().map(|row| (1.5 + 0.3 * row[0] - 0.2 * row[1]).exp()).collect();
+
+        let (intercept, coefficients) = fit_poisson_target(&rows, &targets, 0.0).unwrap();
+        assert!((intercept - 1.5).abs() < 1e-4, "intercept {}", intercept);
+        assert!((coefficients[0] - 0.3).abs() < 1e-4, "coefficient {}", coefficients[0]);
+        assert!((coefficients[1] + 0.2).abs() < 1e-4, "coefficient {}", coefficients[1]);
+    }
+}
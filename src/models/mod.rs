@@ -0,0 +1,47 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub mod boosting;
+pub mod count;
+pub mod dataset;
+pub mod features;
+pub mod linalg;
+pub mod linear;
+pub mod onnx;
+pub mod parity;
+pub mod scaler;
+pub mod xgboost;
+
+/// The columns of the feature matrix that models predict.
+pub const TARGETS: [&str; 2] = ["result.home_team_score", "result.away_team_score"];
+
+/// The directory fitted models are saved in.
+pub const MODELS_DIR: &str = "data/models";
+
+/// A fitted model that predicts every target from a row of unscaled feature values.
+pub trait Regressor {
+    fn feature_names(&self) -> &[String];
+
+    fn predict(&self, row: &[f64]) -> Vec<f64>;
+}
+
+pub(crate) fn load_json<T: DeserializeOwned>(path: &str) -> Result<T, String> {
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read file {}: {}", path, e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse model {}: {}", path, e))
+}
+
+pub(crate) fn save_json<T: Serialize>(value: &T, path: &str) -> Result<(), String> {
+    if let Some(dir) = std::path::Path::new(path).parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create directories: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize model: {}", e))?;
+    std::fs::write(path, json)
+        .map_err(|e| format!("Failed to write file {}: {}", path, e))?;
+
+    Ok(())
+}
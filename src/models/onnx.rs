@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+
+use crate::models::scaler::{ExportedScaler, StandardScaler};
+use crate::models::{load_json, Regressor};
+
+/// A field of a protobuf message, by wire type.
+enum Field<'a> {
+    Varint(u64),
+    /// Not used by any field read here, so skipped.
+    Fixed64,
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+/// Reads the fields of a protobuf message, which is all ONNX files are.
+struct ProtoReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ProtoReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        ProtoReader { bytes, position: 0 }
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.bytes.get(self.position).ok_or("Truncated varint in ONNX file")?;
+            self.position += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err("Varint too long in ONNX file".to_string())
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        let bytes = self.bytes.get(self.position..self.position + length).ok_or("Truncated field in ONNX file")?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    /// The next field number and value, or `None` at the end of the message.
+    fn next_field(&mut self) -> Result<Option<(u64, Field<'a>)>, String> {
+        if self.position >= self.bytes.len() {
+            return Ok(None);
+        }
+
+        let key = self.varint()?;
+        let field = match key & 7 {
+            0 => Field::Varint(self.varint()?),
+            1 => {
+                self.take(8)?;
+                Field::Fixed64
+            },
+            2 => {
+                let length = self.varint()? as usize;
+                Field::Bytes(self.take(length)?)
+            },
+            5 => Field::Fixed32(u32::from_le_bytes(self.take(4)?.try_into().unwrap())),
+            wire_type => return Err(format!("Unsupported protobuf wire type {} in ONNX file", wire_type)),
+        };
+
+        Ok(Some((key >> 3, field)))
+    }
+}
+
+fn string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+/// Read a repeated integer field, which may or may not be packed.
+fn push_integers(field: Field, values: &mut Vec<i64>) -> Result<(), String> {
+    match field {
+        Field::Varint(value) => values.push(value as i64),
+        Field::Bytes(bytes) => {
+            let mut reader = ProtoReader::new(bytes);
+            while reader.position < bytes.len() {
+                values.push(reader.varint()? as i64);
+            }
+        },
+        _ => return Err("Unexpected wire type for integers in ONNX file".to_string()),
+    }
+
+    Ok(())
+}
+
+/// A dense tensor of single-precision floats.
+#[derive(Debug, Clone)]
+struct Tensor {
+    shape: Vec<usize>,
+    data: Vec<f32>,
+}
+
+impl Tensor {
+    /// Parse a `TensorProto` holding floats (or integers, e.g. a shape).
+    fn parse(bytes: &[u8]) -> Result<(String, Tensor), String> {
+        let mut name = String::new();
+        let mut dims = Vec::new();
+        let mut data = Vec::new();
+        let mut integers = Vec::new();
+        let mut raw_data: &[u8] = &[];
+        let mut data_type = 1;
+
+        let mut reader = ProtoReader::new(bytes);
+        while let Some((number, field)) = reader.next_field()? {
+            match (number, field) {
+                (1, field) => push_integers(field, &mut dims)?,
+                (2, Field::Varint(value)) => data_type = value,
+                (4, Field::Fixed32(value)) => data.push(f32::from_bits(value)),
+                (4, Field::Bytes(packed)) => data.extend(packed.chunks_exact(4).map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))),
+                (7, field) => push_integers(field, &mut integers)?,
+                (8, Field::Bytes(value)) => name = string(value),
+                (9, Field::Bytes(value)) => raw_data = value,
+                _ => {},
+            }
+        }
+
+        // 1 is FLOAT and 7 is INT64
+        match data_type {
+            1 => data.extend(raw_data.chunks_exact(4).map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))),
+            7 => {
+                data.extend(integers.iter().map(|value| *value as f32));
+                data.extend(raw_data.chunks_exact(8).map(|chunk| i64::from_le_bytes(chunk.try_into().unwrap()) as f32));
+            },
+            _ => return Err(format!("Unsupported ONNX tensor data type {} for {}", data_type, name)),
+        }
+
+        Ok((
+            name,
+            Tensor {
+                shape: dims.into_iter().map(|dim| dim as usize).collect(),
+                data,
+            },
+        ))
+    }
+
+    fn rows_and_columns(&self) -> (usize, usize) {
+        match self.shape.as_slice() {
+            [columns] => (1, *columns),
+            [rows, columns] => (*rows, *columns),
+            _ => (1, self.data.len()),
+        }
+    }
+
+    fn transposed(&self) -> Tensor {
+        let (rows, columns) = self.rows_and_columns();
+        let mut data = vec![0.0; self.data.len()];
+        for row in 0..rows {
+            for column in 0..columns {
+                data[column * rows + row] = self.data[row * columns + column];
+            }
+        }
+
+        Tensor { shape: vec![columns, rows], data }
+    }
+
+    fn matmul(&self, other: &Tensor) -> Result<Tensor, String> {
+        let (rows, inner) = self.rows_and_columns();
+        let (other_rows, columns) = other.rows_and_columns();
+        if inner != other_rows {
+            return Err(format!("Cannot multiply ONNX tensors of shapes {:?} and {:?}", self.shape, other.shape));
+        }
+
+        let mut data = vec![0.0; rows * columns];
+        for row in 0..rows {
+            for k in 0..inner {
+                let value = self.data[row * inner + k];
+                for column in 0..columns {
+                    data[row * columns + column] += value * other.data[k * columns + column];
+                }
+            }
+        }
+
+        Ok(Tensor { shape: vec![rows, columns], data })
+    }
+
+    /// Combine two tensors elementwise, broadcasting the smaller over the trailing dimensions of the larger (e.g. a
+    /// bias over the rows of a batch).
+    fn elementwise(&self, other: &Tensor, op: impl Fn(f32, f32) -> f32) -> Result<Tensor, String> {
+        let (larger, smaller, swapped) = if self.data.len() >= other.data.len() { (self, other, false) } else { (other, self, true) };
+        if smaller.data.is_empty() || larger.data.len() % smaller.data.len() != 0 {
+            return Err(format!("Cannot broadcast ONNX tensors of shapes {:?} and {:?}", self.shape, other.shape));
+        }
+
+        let data = larger
+            .data
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let other_value = smaller.data[i % smaller.data.len()];
+                if swapped { op(other_value, *value) } else { op(*value, other_value) }
+            })
+            .collect();
+
+        Ok(Tensor { shape: larger.shape.clone(), data })
+    }
+
+    fn map(&self, op: impl Fn(f32) -> f32) -> Tensor {
+        Tensor {
+            shape: self.shape.clone(),
+            data: self.data.iter().map(|value| op(*value)).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    op_type: String,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    float_attributes: HashMap<String, f32>,
+    int_attributes: HashMap<String, i64>,
+}
+
+impl Node {
+    fn parse(bytes: &[u8]) -> Result<Node, String> {
+        let mut node = Node {
+            op_type: String::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            float_attributes: HashMap::new(),
+            int_attributes: HashMap::new(),
+        };
+
+        let mut reader = ProtoReader::new(bytes);
+        while let Some((number, field)) = reader.next_field()? {
+            match (number, field) {
+                (1, Field::Bytes(input)) => node.inputs.push(string(input)),
+                (2, Field::Bytes(output)) => node.outputs.push(string(output)),
+                (4, Field::Bytes(op_type)) => node.op_type = string(op_type),
+                (5, Field::Bytes(attribute)) => {
+                    let mut name = String::new();
+                    let mut float = None;
+                    let mut int = None;
+                    let mut attribute_reader = ProtoReader::new(attribute);
+                    while let Some((number, field)) = attribute_reader.next_field()? {
+                        match (number, field) {
+                            (1, Field::Bytes(value)) => name = string(value),
+                            (2, Field::Fixed32(value)) => float = Some(f32::from_bits(value)),
+                            (3, Field::Varint(value)) => int = Some(value as i64),
+                            _ => {},
+                        }
+                    }
+                    if let Some(float) = float {
+                        node.float_attributes.insert(name.clone(), float);
+                    }
+                    if let Some(int) = int {
+                        node.int_attributes.insert(name, int);
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        Ok(node)
+    }
+}
+
+/// The operators [`OnnxModel`] can run: the ones a dense network exports to.
+const SUPPORTED_OPERATORS: [&str; 10] = ["MatMul", "Gemm", "Add", "Sub", "Mul", "Relu", "Sigmoid", "Tanh", "Identity", "Dropout"];
+
+/// A feed-forward network trained by `training/nn.py` and exported to ONNX, run on the CPU. Only the operators a
+/// dense network exports to are supported, which is checked when the model is loaded.
+#[derive(Debug, Clone)]
+pub struct OnnxModel {
+    pub feature_names: Vec<String>,
+    pub scaler: StandardScaler,
+    nodes: Vec<Node>,
+    initializers: HashMap<String, Tensor>,
+    input: String,
+    output: String,
+}
+
+impl OnnxModel {
+    pub fn load(model_path: &str, scaler_path: &str) -> Result<Self, String> {
+        let ExportedScaler { feature_names, scaler } = load_json(scaler_path)?;
+        let bytes = std::fs::read(model_path)
+            .map_err(|e| format!("Failed to read file {}: {}", model_path, e))?;
+
+        // ModelProto.graph
+        let mut graph = None;
+        let mut reader = ProtoReader::new(&bytes);
+        while let Some((number, field)) = reader.next_field()? {
+            if let (7, Field::Bytes(value)) = (number, field) {
+                graph = Some(value);
+            }
+        }
+        let graph = graph.ok_or(format!("ONNX file {} has no graph", model_path))?;
+
+        let mut nodes = Vec::new();
+        let mut initializers = HashMap::new();
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        let mut reader = ProtoReader::new(graph);
+        while let Some((number, field)) = reader.next_field()? {
+            match (number, field) {
+                (1, Field::Bytes(node)) => nodes.push(Node::parse(node)?),
+                (5, Field::Bytes(tensor)) => {
+                    let (name, tensor) = Tensor::parse(tensor)?;
+                    initializers.insert(name, tensor);
+                },
+                (11 | 12, Field::Bytes(value_info)) => {
+                    let mut name = String::new();
+                    let mut value_reader = ProtoReader::new(value_info);
+                    while let Some((value_number, value_field)) = value_reader.next_field()? {
+                        if let (1, Field::Bytes(value)) = (value_number, value_field) {
+                            name = string(value);
+                        }
+                    }
+                    if number == 11 { inputs.push(name) } else { outputs.push(name) }
+                },
+                _ => {},
+            }
+        }
+
+        // older exporters also list the initializers as inputs
+        let input = inputs
+            .into_iter()
+            .find(|input| !initializers.contains_key(input))
+            .ok_or(format!("ONNX file {} has no input", model_path))?;
+        let output = outputs.into_iter().next().ok_or(format!("ONNX file {} has no output", model_path))?;
+
+        for node in &nodes {
+            if !SUPPORTED_OPERATORS.contains(&node.op_type.as_str()) {
+                return Err(format!("Unsupported ONNX operator {} in {}", node.op_type, model_path));
+            }
+            if node.outputs.is_empty() {
+                return Err(format!("ONNX {} node has no output in {}", node.op_type, model_path));
+            }
+        }
+
+        let model = OnnxModel {
+            feature_names,
+            scaler,
+            nodes,
+            initializers,
+            input,
+            output,
+        };
+        // run the graph once, so that mismatched shapes or undefined values fail here rather than when predicting
+        model.run(&[vec![0.0; model.feature_names.len()]])
+            .map_err(|e| format!("Failed to run ONNX file {}: {}", model_path, e))?;
+
+        Ok(model)
+    }
+
+    /// Run the graph on a batch of rows of scaled features. The nodes of an ONNX graph are in topological order.
+    fn run(&self, rows: &[Vec<f32>]) -> Result<Tensor, String> {
+        let mut values: HashMap<&str, Tensor> = HashMap::new();
+        values.insert(
+            &self.input,
+            Tensor {
+                shape: vec![rows.len(), rows.first().map_or(0, |row| row.len())],
+                data: rows.concat(),
+            },
+        );
+
+        for node in &self.nodes {
+            let input = |i: usize| -> Result<&Tensor, String> {
+                let name = node.inputs.get(i).ok_or(format!("ONNX {} node is missing input {}", node.op_type, i))?;
+                values.get(name.as_str()).or(self.initializers.get(name)).ok_or(format!("ONNX value {} is undefined", name))
+            };
+
+            let output = match node.op_type.as_str() {
+                "MatMul" => input(0)?.matmul(input(1)?)?,
+                "Gemm" => {
+                    let a = if node.int_attributes.get("transA") == Some(&1) { input(0)?.transposed() } else { input(0)?.clone() };
+                    let b = if node.int_attributes.get("transB") == Some(&1) { input(1)?.transposed() } else { input(1)?.clone() };
+                    let alpha = node.float_attributes.get("alpha").copied().unwrap_or(1.0);
+                    let beta = node.float_attributes.get("beta").copied().unwrap_or(1.0);
+
+                    let product = a.matmul(&b)?.map(|value| alpha * value);
+                    match node.inputs.get(2).filter(|name| !name.is_empty()) {
+                        Some(_) => product.elementwise(input(2)?, |x, c| x + beta * c)?,
+                        None => product,
+                    }
+                },
+                "Add" => input(0)?.elementwise(input(1)?, |a, b| a + b)?,
+                "Sub" => input(0)?.elementwise(input(1)?, |a, b| a - b)?,
+                "Mul" => input(0)?.elementwise(input(1)?, |a, b| a * b)?,
+                "Relu" => input(0)?.map(|x| x.max(0.0)),
+                "Sigmoid" => input(0)?.map(|x| 1.0 / (1.0 + (-x).exp())),
+                "Tanh" => input(0)?.map(|x| x.tanh()),
+                // dropout does nothing at inference time
+                "Identity" | "Dropout" => input(0)?.clone(),
+                op_type => return Err(format!("Unsupported ONNX operator {}", op_type)),
+            };
+            values.insert(&node.outputs[0], output);
+        }
+
+        values.remove(self.output.as_str()).ok_or(format!("ONNX output {} was never computed", self.output))
+    }
+}
+
+impl Regressor for OnnxModel {
+    fn feature_names(&self) -> &[String] {
+        &self.feature_names
+    }
+
+    /// Missing values are replaced with the training mean after scaling, as in `training/nn.py`.
+    fn predict(&self, row: &[f64]) -> Vec<f64> {
+        let row: Vec<f32> = self.scaler.transform_filled(row).into_iter().map(|value| value as f32).collect();
+        self.run(&[row]).expect("the graph ran when the model was loaded").data.into_iter().map(|value| value as f64).collect()
+    }
+}
@@ -0,0 +1,44 @@
+use serde::Deserialize;
+
+use crate::models::{load_json, Regressor};
+
+/// The largest difference from a fixture's predictions that still counts as identical, allowing for single-precision
+/// arithmetic in a different order.
+pub const TOLERANCE: f64 = 1e-4;
+
+/// Unscaled feature rows and a Python model's predictions for them, as exported by `training/export.py`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fixture {
+    pub feature_names: Vec<String>,
+    /// Missing values are `None`.
+    pub rows: Vec<Vec<Option<f64>>>,
+    pub predictions: Vec<Vec<f64>>,
+}
+
+impl Fixture {
+    pub fn load(path: &str) -> Result<Self, String> {
+        load_json(path)
+    }
+}
+
+/// Predict every row of a fixture, returning the largest difference from the Python predictions.
+pub fn max_difference(model: &dyn Regressor, fixture: &Fixture) -> Result<f64, String> {
+    if model.feature_names() != fixture.feature_names.as_slice() {
+        return Err("The fixture's features don't match the model's".to_string());
+    }
+
+    let mut max_difference: f64 = 0.0;
+    for (row, expected) in fixture.rows.iter().zip(&fixture.predictions) {
+        let row: Vec<f64> = row.iter().map(|value| value.unwrap_or(f64::NAN)).collect();
+        let predicted = model.predict(&row);
+        if predicted.len() != expected.len() {
+            return Err(format!("The model predicts {} targets, but the fixture has {}", predicted.len(), expected.len()));
+        }
+
+        for (predicted, expected) in predicted.iter().zip(expected) {
+            max_difference = max_difference.max((predicted - expected).abs());
+        }
+    }
+
+    Ok(max_difference)
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// Standardizes each feature to zero mean and unit variance, like scikit-learn's `StandardScaler`: the variance is the
+/// population variance, NaNs are ignored when fitting and kept when transforming, and constant features are left
+/// unscaled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandardScaler {
+    pub means: Vec<f64>,
+    pub scales: Vec<f64>,
+}
+
+impl StandardScaler {
+    pub fn fit(rows: &[Vec<f64>]) -> Self {
+        let width = rows.first().map_or(0, |row| row.len());
+
+        let mut means = vec![0.0; width];
+        let mut scales = vec![1.0; width];
+        for column in 0..width {
+            let values: Vec<f64> = rows.iter().map(|row| row[column]).filter(|value| !value.is_nan()).collect();
+            if values.is_empty() {
+                continue;
+            }
+
+            let mean = values.iter().sum::<f64>() / values.len() as f64;
+            let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / values.len() as f64;
+            means[column] = mean;
+            if variance > 0.0 {
+                scales[column] = variance.sqrt();
+            }
+        }
+
+        StandardScaler { means, scales }
+    }
+
+    pub fn transform(&self, row: &[f64]) -> Vec<f64> {
+        row.iter()
+            .zip(self.means.iter().zip(&self.scales))
+            .map(|(value, (mean, scale))| (value - mean) / scale)
+            .collect()
+    }
+
+    /// Transform a row, replacing missing values with zero (the training mean), like `np.nan_to_num` after scaling
+    /// in `training/nn.py`.
+    pub fn transform_filled(&self, row: &[f64]) -> Vec<f64> {
+        self.transform(row).into_iter().map(|value| if value.is_nan() { 0.0 } else { value }).collect()
+    }
+}
+
+/// The scaler a Python model was trained with, as exported by `training/export.py`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedScaler {
+    pub feature_names: Vec<String>,
+    #[serde(flatten)]
+    pub scaler: StandardScaler,
+}
@@ -0,0 +1,139 @@
+use serde_json::Value;
+
+use crate::models::scaler::{ExportedScaler, StandardScaler};
+use crate::models::{load_json, Regressor};
+
+/// One tree of an XGBoost model, in the array layout of its JSON dump.
+#[derive(Debug, Clone)]
+struct XGBoostTree {
+    left_children: Vec<i32>,
+    right_children: Vec<i32>,
+    split_indices: Vec<usize>,
+    /// The split threshold of a node, or the value of a leaf.
+    split_conditions: Vec<f32>,
+    default_left: Vec<bool>,
+    /// The index of the target the tree contributes to.
+    target: usize,
+}
+
+impl XGBoostTree {
+    fn from_json(tree: &Value, target: usize) -> Result<Self, String> {
+        let array = |key: &str| tree[key].as_array().ok_or(format!("XGBoost tree has no {} array", key));
+
+        Ok(XGBoostTree {
+            left_children: array("left_children")?.iter().map(|child| child.as_i64().unwrap_or(-1) as i32).collect(),
+            right_children: array("right_children")?.iter().map(|child| child.as_i64().unwrap_or(-1) as i32).collect(),
+            split_indices: array("split_indices")?.iter().map(|index| index.as_u64().unwrap_or(0) as usize).collect(),
+            split_conditions: array("split_conditions")?.iter().map(|condition| condition.as_f64().unwrap_or(0.0) as f32).collect(),
+            // older versions write booleans, newer ones 0 and 1
+            default_left: array("default_left")?.iter().map(|flag| flag.as_bool().unwrap_or(flag.as_u64() == Some(1))).collect(),
+            target,
+        })
+    }
+
+    /// Follow the tree to a leaf like XGBoost does: in single precision, going left if the feature is strictly less
+    /// than the threshold, and the node's default way if it's missing.
+    fn predict(&self, row: &[f32]) -> f32 {
+        let mut node = 0;
+        while self.left_children[node] >= 0 {
+            let value = row[self.split_indices[node]];
+            let goes_left = if value.is_nan() { self.default_left[node] } else { value < self.split_conditions[node] };
+            node = if goes_left { self.left_children[node] } else { self.right_children[node] } as usize;
+        }
+
+        self.split_conditions[node]
+    }
+}
+
+/// A gradient-boosted tree model trained by `training/train.py`, loaded from XGBoost's JSON format together with the
+/// scaler its features were standardized with.
+#[derive(Debug, Clone)]
+pub struct XGBoostModel {
+    pub feature_names: Vec<String>,
+    pub scaler: StandardScaler,
+    /// Whether the objective predicts the log of the target (e.g. `count:poisson`).
+    log_link: bool,
+    base_margins: Vec<f64>,
+    trees: Vec<XGBoostTree>,
+}
+
+impl XGBoostModel {
+    pub fn load(model_path: &str, scaler_path: &str) -> Result<Self, String> {
+        let ExportedScaler { feature_names, scaler } = load_json(scaler_path)?;
+        let model: Value = load_json(model_path)?;
+        let learner = &model["learner"];
+
+        let objective = learner["objective"]["name"].as_str().ok_or("XGBoost model has no objective")?;
+        let log_link = matches!(objective, "count:poisson" | "reg:gamma" | "reg:tweedie");
+
+        let booster = &learner["gradient_booster"];
+        if booster["name"].as_str() != Some("gbtree") {
+            return Err(format!("Unsupported XGBoost booster {}", booster["name"]));
+        }
+        let trees = booster["model"]["trees"].as_array().ok_or("XGBoost model has no trees")?;
+        let tree_info: Vec<usize> = booster["model"]["tree_info"]
+            .as_array()
+            .map(|info| info.iter().map(|target| target.as_u64().unwrap_or(0) as usize).collect())
+            .unwrap_or_default();
+
+        let parse_number = |value: &Value| value.as_str().and_then(|value| value.parse::<f64>().ok());
+        let num_targets = parse_number(&learner["learner_model_param"]["num_target"]).unwrap_or(1.0).max(1.0) as usize;
+
+        // after early stopping, Python predicts with the trees up to the best iteration
+        let mut tree_count = trees.len();
+        if let Some(best_iteration) = parse_number(&learner["attributes"]["best_iteration"]) {
+            let rounds = best_iteration as usize + 1;
+            tree_count = match booster["model"]["iteration_indptr"].as_array() {
+                Some(indptr) => indptr.get(rounds).and_then(|end| end.as_u64()).map_or(tree_count, |end| end as usize),
+                None => rounds * num_targets,
+            }
+            .min(tree_count);
+        }
+        let trees = trees
+            .iter()
+            .take(tree_count)
+            .enumerate()
+            .map(|(i, tree)| XGBoostTree::from_json(tree, tree_info.get(i).copied().unwrap_or(0)))
+            .collect::<Result<Vec<XGBoostTree>, String>>()?;
+
+        // the base score is in the target's units, as a single number or (for several targets) a list
+        let base_score = learner["learner_model_param"]["base_score"].as_str().ok_or("XGBoost model has no base score")?;
+        let base_scores = base_score
+            .trim_matches(|c| c == '[' || c == ']')
+            .split(',')
+            .map(|score| score.trim().parse::<f64>().map_err(|e| format!("Failed to parse base score {}: {}", base_score, e)))
+            .collect::<Result<Vec<f64>, String>>()?;
+        let base_margins = (0..num_targets)
+            .map(|target| {
+                let score = base_scores.get(target).copied().unwrap_or(base_scores[0]);
+                if log_link { score.ln() } else { score }
+            })
+            .collect();
+
+        Ok(XGBoostModel {
+            feature_names,
+            scaler,
+            log_link,
+            base_margins,
+            trees,
+        })
+    }
+}
+
+impl Regressor for XGBoostModel {
+    fn feature_names(&self) -> &[String] {
+        &self.feature_names
+    }
+
+    /// Missing values stay missing after scaling, and take each split's default way.
+    fn predict(&self, row: &[f64]) -> Vec<f64> {
+        let row: Vec<f32> = self.scaler.transform(row).into_iter().map(|value| value as f32).collect();
+
+        let mut margins = self.base_margins.clone();
+        for tree in &self.trees {
+            margins[tree.target] += tree.predict(&row) as f64;
+        }
+
+        margins.into_iter().map(|margin| if self.log_link { margin.exp() } else { margin }).collect()
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use crate::history;
+use crate::model::Game;
+
+/// The number of seasons before a game's own whose games count towards its park factor.
+const PRIOR_SEASONS: u16 = 2;
+
+/// The number of games' worth of a neutral park factor (1.0) mixed into every estimate.
+const REGRESSION_GAMES: f32 = 150.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Park {
+    Venue(u16),
+    /// The usual park of a home team, for games stored before venues were recorded.
+    HomeOf(u8),
+}
+
+/// Find the park of every game.
+fn parks(games: &[Game]) -> Vec<Park> {
+    let home_venues = history::home_venues(games);
+
+    games
+        .iter()
+        .map(|game| match history::venue_of(game, &home_venues) {
+            Some(venue) => Park::Venue(venue.id),
+            None => Park::HomeOf(game.home_team.team_id),
+        })
+        .collect()
+}
+
+/// Estimate the park factor of every park from the given games.
+///
+/// A park's raw factor is the total runs scored there divided by the total expected from the two teams' average
+/// runs per road game (scored plus allowed), which is then regressed towards 1.0 by [`REGRESSION_GAMES`].
+fn park_factors(games: &[Game], parks: &[Park], window: &[usize]) -> HashMap<Park, f32> {
+    let mut road: HashMap<u8, (f32, f32)> = HashMap::new();
+    for &i in window {
+        let result = &games[i].result;
+        let road_team = road.entry(games[i].away_team.team_id).or_default();
+        road_team.0 += (result.home_team_score + result.away_team_score) as f32;
+        road_team.1 += 1.0;
+    }
+    let road_runs_per_game = |team_id: u8| road.get(&team_id).map(|(runs, games)| runs / games);
+
+    let mut totals: HashMap<Park, (f32, f32, f32)> = HashMap::new();
+    for &i in window {
+        let game = &games[i];
+        let (Some(home_expected), Some(away_expected)) = (road_runs_per_game(game.home_team.team_id), road_runs_per_game(game.away_team.team_id)) else {
+            continue;
+        };
+        let park = totals.entry(parks[i]).or_default();
+        park.0 += (game.result.home_team_score + game.result.away_team_score) as f32;
+        park.1 += (home_expected + away_expected) / 2.0;
+        park.2 += 1.0;
+    }
+
+    totals
+        .into_iter()
+        .map(|(park, (actual, expected, count))| {
+            let raw = actual / expected;
+            (park, (raw * count + REGRESSION_GAMES) / (count + REGRESSION_GAMES))
+        })
+        .collect()
+}
+
+/// Set the park factor of every game from the games before it in its season and the [`PRIOR_SEASONS`] before that.
+///
+/// `games` must be in chronological order. Games with no earlier games to learn from get no park factor, and parks
+/// with no earlier games get a neutral one.
+pub fn assign_park_factors(games: &mut [Game]) {
+    let parks = parks(games);
+
+    let mut start = 0;
+    while start < games.len() {
+        let date = games[start].setting.date;
+        let end = start + games[start..].iter().take_while(|game| game.setting.date == date).count();
+
+        let window: Vec<usize> = (0..start)
+            .filter(|&i| games[i].setting.date.year + PRIOR_SEASONS >= date.year)
+            .collect();
+        let factors = park_factors(games, &parks, &window);
+
+        for i in start..end {
+            games[i].setting.park_factor = if window.is_empty() {
+                None
+            } else {
+                Some(factors.get(&parks[i]).copied().unwrap_or(1.0))
+            };
+        }
+        start = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every pair of three teams meets once in each park, with `park_3_runs` scored in team 3's park and 8 in the
+    /// others, followed by one game in each park.
+    fn games(park_3_runs: u8) -> Vec<Game> {
+        let pairs = [(1, 2), (2, 3), (3, 1), (2, 1), (3, 2), (1, 3), (1, 2), (2, 3), (3, 1)];
+        pairs
+            .iter()
+            .enumerate()
+            .map(|(day, &(home, away))| {
+                let runs = if home == 3 { park_3_runs } else { 8 };
+                Game::with_score(&format!("2024-04-{:02}", day + 1), home, away, runs / 2, runs - runs / 2)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn league_average_parks_are_neutral() {
+        let mut games = games(8);
+        assign_park_factors(&mut games);
+
+        assert_eq!(games[0].setting.park_factor, None);
+        // the last three games' factors come from the six before them, all scoring the league average
+        for game in &games[6..] {
+            assert_eq!(game.setting.park_factor, Some(1.0));
+        }
+    }
+
+    #[test]
+    fn high_scoring_parks_are_above_neutral() {
+        let mut games = games(14);
+        assign_park_factors(&mut games);
+
+        let factor = games[8].setting.park_factor.unwrap();
+        assert!(factor > 1.0 && factor < 14.0 / 8.0, "factor {}", factor);
+        assert!(games[6].setting.park_factor.unwrap() < 1.0);
+    }
+}
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+use crate::boxscore::PitcherAppearance;
+use crate::model::{fetch_json, ratio, Date};
+
+/// The number of most recent starts summarized in [`StartingPitcher::recent`].
+const RECENT_STARTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hand {
+    Left,
+    Right,
+    Switch,
+}
+
+impl Hand {
+    /// Parse an MLB hand code (`"L"`, `"R"` or `"S"`).
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "L" => Some(Hand::Left),
+            "R" => Some(Hand::Right),
+            "S" => Some(Hand::Switch),
+            _ => None,
+        }
+    }
+}
+
+/// A pitcher's combined line over a set of appearances.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PitchingLine {
+    pub games: u8,
+    pub games_started: u8,
+    pub outs: u16,
+    pub batters_faced: u16,
+    pub number_of_pitches: u16,
+    pub hits: u16,
+    pub home_runs: u16,
+    pub base_on_balls: u16,
+    pub strike_outs: u16,
+    pub runs: u16,
+    pub earned_runs: u16,
+    pub era: Option<f32>,
+    pub whip: Option<f32>,
+    pub strikeouts_per_9inn: Option<f32>,
+    pub walks_per_9inn: Option<f32>,
+}
+
+impl PitchingLine {
+    /// Sum the `stat` objects of a pitcher's game log splits.
+    fn from_splits<'a>(splits: impl Iterator<Item = &'a serde_json::Value>) -> Self {
+        let mut line = PitchingLine::default();
+        for split in splits {
+            let stat = &split["stat"];
+            let count = |key: &str| stat[key].as_u64().unwrap_or(0) as u16;
+
+            line.games += 1;
+            line.games_started += count("gamesStarted") as u8;
+            line.outs += count("outs");
+            line.batters_faced += count("battersFaced");
+            line.number_of_pitches += count("numberOfPitches");
+            line.hits += count("hits");
+            line.home_runs += count("homeRuns");
+            line.base_on_balls += count("baseOnBalls");
+            line.strike_outs += count("strikeOuts");
+            line.runs += count("runs");
+            line.earned_runs += count("earnedRuns");
+        }
+        line.fill_rates();
+
+        line
+    }
+
+    /// Sum a set of stored single-game pitching lines.
+    pub fn from_appearances<'a>(appearances: impl Iterator<Item = &'a PitcherAppearance>) -> Self {
+        let mut line = PitchingLine::default();
+        for appearance in appearances {
+            line.games += 1;
+            line.games_started += appearance.starter as u8;
+            line.outs += appearance.outs;
+            line.batters_faced += appearance.batters_faced;
+            line.number_of_pitches += appearance.number_of_pitches;
+            line.hits += appearance.hits;
+            line.home_runs += appearance.home_runs;
+            line.base_on_balls += appearance.base_on_balls;
+            line.strike_outs += appearance.strike_outs;
+            line.runs += appearance.runs;
+            line.earned_runs += appearance.earned_runs;
+        }
+        line.fill_rates();
+
+        line
+    }
+
+    fn fill_rates(&mut self) {
+        let outs = self.outs as f32;
+        self.era = ratio(27.0 * self.earned_runs as f32, outs);
+        self.whip = ratio(3.0 * (self.hits + self.base_on_balls) as f32, outs);
+        self.strikeouts_per_9inn = ratio(27.0 * self.strike_outs as f32, outs);
+        self.walks_per_9inn = ratio(27.0 * self.base_on_balls as f32, outs);
+    }
+}
+
+/// A team's starting pitcher for a game, with their form going into it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartingPitcher {
+    pub player_id: u32,
+    pub hand: Option<Hand>,
+    /// The pitcher's line over their last few starts before the game.
+    pub recent: PitchingLine,
+    /// The pitcher's line over all of their appearances in the season before the game.
+    pub season: PitchingLine,
+}
+
+impl StartingPitcher {
+    /// Get the pitcher's handedness and his form going into a game on `date`.
+    ///
+    /// Only appearances strictly before `date` count, the same cutoff as the team, bullpen and standings stats fetched
+    /// with the game, so the line never includes the game itself. A pitcher with no game log yet (e.g. on their debut)
+    /// gets empty lines.
+    pub async fn by_date(player_id: u32, date: &Date) -> Result<Self, String> {
+        let url = format!(
+            "https://statsapi.mlb.com/api/v1/people/{}?hydrate=stats(group=[pitching],type=[gameLog],season={})",
+            player_id,
+            date.year,
+        );
+        let person = fetch_json(&url).await?;
+        let person = &person["people"][0];
+
+        let hand = person["pitchHand"]["code"].as_str().and_then(Hand::from_code);
+
+        let splits = person["stats"][0]["splits"].as_array().map(Vec::as_slice).unwrap_or_default();
+        let mut appearances: Vec<&serde_json::Value> = splits
+            .iter()
+            .filter(|split| split["date"].as_str().is_some_and(|split_date| Date::from(split_date) < *date))
+            .collect();
+        appearances.sort_by_key(|split| Date::from(split["date"].as_str().unwrap()));
+
+        let starts: Vec<&serde_json::Value> = appearances
+            .iter()
+            .filter(|split| split["stat"]["gamesStarted"].as_u64().unwrap_or(0) > 0)
+            .copied()
+            .collect();
+        let recent_starts = &starts[starts.len().saturating_sub(RECENT_STARTS)..];
+
+        Ok(StartingPitcher {
+            player_id,
+            hand,
+            recent: PitchingLine::from_splits(recent_starts.iter().copied()),
+            season: PitchingLine::from_splits(appearances.into_iter()),
+        })
+    }
+}
@@ -0,0 +1,333 @@
+use indicatif::ProgressIterator;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{fetch_json, Game};
+
+/// The columns of a stored play-by-play file, one plate appearance per line.
+const HEADER: &str = "at_bat_index,inning,half,batter_id,pitcher_id,balls,strikes,outcome,bases_before,outs_before,bases_after,outs_after,runs_scored";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HalfInning {
+    Top,
+    Bottom,
+}
+
+/// The runners on base and the number of outs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BaseOutState {
+    /// The occupied bases as bits: 1 for first, 2 for second and 4 for third.
+    pub bases: u8,
+    pub outs: u8,
+}
+
+impl BaseOutState {
+    /// The state's index among the 24 base-out states (`bases + 8 * outs`), or `None` once the inning is over.
+    pub fn index(&self) -> Option<usize> {
+        (self.outs < 3).then_some(self.bases as usize + 8 * self.outs as usize)
+    }
+}
+
+fn base_bit(base: &serde_json::Value) -> u8 {
+    match base.as_str() {
+        Some("1B") => 1,
+        Some("2B") => 2,
+        Some("3B") => 4,
+        _ => 0,
+    }
+}
+
+/// The bases occupied and the runs scored after a play's runner movements.
+///
+/// A play lists a movement for each segment of each runner's way around the bases (e.g. a steal and then an advance on
+/// the hit), and not necessarily in order, so every base a runner leaves is cleared before the bases they end the play
+/// on are set, and each runner only counts once, by the movement that takes them furthest (or puts them out).
+fn move_runners(bases: u8, runners: &[serde_json::Value]) -> (u8, u8) {
+    let progress = |movement: &serde_json::Value| match movement["end"].as_str() {
+        _ if movement["isOut"].as_bool() == Some(true) => 5,
+        Some("score") => 4,
+        Some("3B") => 3,
+        Some("2B") => 2,
+        Some("1B") => 1,
+        _ => 0,
+    };
+
+    let mut final_movements: Vec<(Option<u64>, &serde_json::Value)> = Vec::new();
+    let mut bases = bases;
+    for runner in runners {
+        let movement = &runner["movement"];
+        bases &= !base_bit(&movement["start"]);
+
+        let runner_id = runner["details"]["runner"]["id"].as_u64();
+        match final_movements.iter_mut().find(|(id, _)| runner_id.is_some() && *id == runner_id) {
+            Some(final_movement) if progress(movement) >= progress(final_movement.1) => final_movement.1 = movement,
+            Some(_) => {},
+            None => final_movements.push((runner_id, movement)),
+        }
+    }
+
+    let mut runs_scored = 0;
+    for (_, movement) in final_movements {
+        if movement["isOut"].as_bool() == Some(true) {
+            continue;
+        }
+        if movement["end"].as_str() == Some("score") {
+            runs_scored += 1;
+        } else {
+            bases |= base_bit(&movement["end"]);
+        }
+    }
+
+    (bases, runs_scored)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlateAppearance {
+    pub at_bat_index: u16,
+    pub inning: u8,
+    pub half: HalfInning,
+    pub batter_id: u32,
+    pub pitcher_id: u32,
+    /// The count when the plate appearance ended.
+    pub balls: u8,
+    pub strikes: u8,
+    /// The MLB event type of the result (e.g. `single`, `strikeout`, `field_out`).
+    pub outcome: String,
+    pub before: BaseOutState,
+    pub after: BaseOutState,
+    /// Runs scored during the plate appearance, including on steals, wild pitches and the like.
+    pub runs_scored: u8,
+}
+
+impl PlateAppearance {
+    fn to_line(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.at_bat_index,
+            self.inning,
+            match self.half {
+                HalfInning::Top => "top",
+                HalfInning::Bottom => "bottom",
+            },
+            self.batter_id,
+            self.pitcher_id,
+            self.balls,
+            self.strikes,
+            self.outcome,
+            self.before.bases,
+            self.before.outs,
+            self.after.bases,
+            self.after.outs,
+            self.runs_scored,
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() != 13 {
+            return None;
+        }
+
+        Some(PlateAppearance {
+            at_bat_index: fields[0].parse().ok()?,
+            inning: fields[1].parse().ok()?,
+            half: match fields[2] {
+                "top" => HalfInning::Top,
+                "bottom" => HalfInning::Bottom,
+                _ => return None,
+            },
+            batter_id: fields[3].parse().ok()?,
+            pitcher_id: fields[4].parse().ok()?,
+            balls: fields[5].parse().ok()?,
+            strikes: fields[6].parse().ok()?,
+            outcome: fields[7].to_string(),
+            before: BaseOutState {
+                bases: fields[8].parse().ok()?,
+                outs: fields[9].parse().ok()?,
+            },
+            after: BaseOutState {
+                bases: fields[10].parse().ok()?,
+                outs: fields[11].parse().ok()?,
+            },
+            runs_scored: fields[12].parse().ok()?,
+        })
+    }
+}
+
+/// Every plate appearance of a game, stored in `data/plays/<gamePk>.csv`.
+#[derive(Debug, Clone)]
+pub struct GamePlays {
+    pub game_pk: u32,
+    pub plate_appearances: Vec<PlateAppearance>,
+}
+
+impl GamePlays {
+    /// Load the game's plays from disk, fetching and saving them first if they haven't been stored yet.
+    pub async fn get(game_pk: u32) -> Result<Self, String> {
+        if let Some(plays) = GamePlays::load(game_pk) {
+            return Ok(plays);
+        }
+
+        let plays = GamePlays::fetch(game_pk).await?;
+        plays.save()?;
+
+        Ok(plays)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PlateAppearance> {
+        self.plate_appearances.iter()
+    }
+
+    async fn fetch(game_pk: u32) -> Result<Self, String> {
+        let url = format!("https://statsapi.mlb.com/api/v1/game/{}/playByPlay", game_pk);
+        let play_by_play = fetch_json(&url).await?;
+        let Some(plays) = play_by_play["allPlays"].as_array() else {
+            return Err(format!("Failed to parse play-by-play: {url}"));
+        };
+
+        let mut plate_appearances = Vec::new();
+        let mut current_half_inning = None;
+        let mut state = BaseOutState { bases: 0, outs: 0 };
+        for play in plays {
+            let about = &play["about"];
+            let (Some(inning), Some(half)) = (about["inning"].as_u64(), about["halfInning"].as_str()) else {
+                continue;
+            };
+            let half = if half == "top" { HalfInning::Top } else { HalfInning::Bottom };
+            if current_half_inning != Some((inning, half)) {
+                current_half_inning = Some((inning, half));
+                state = BaseOutState { bases: 0, outs: 0 };
+            }
+
+            // runners who started the play on base, which also catches runners placed on second in extra innings
+            let runners = play["runners"].as_array().map(Vec::as_slice).unwrap_or_default();
+            let before = BaseOutState {
+                bases: runners.iter().fold(state.bases, |bases, runner| bases | base_bit(&runner["movement"]["originBase"])),
+                outs: state.outs,
+            };
+
+            let (bases, runs_scored) = move_runners(before.bases, runners);
+            let outs = play["count"]["outs"].as_u64().unwrap_or(before.outs as u64).min(3) as u8;
+            state = BaseOutState { bases, outs };
+
+            plate_appearances.push(PlateAppearance {
+                at_bat_index: about["atBatIndex"].as_u64().unwrap_or(0) as u16,
+                inning: inning as u8,
+                half,
+                batter_id: play["matchup"]["batter"]["id"].as_u64().unwrap_or(0) as u32,
+                pitcher_id: play["matchup"]["pitcher"]["id"].as_u64().unwrap_or(0) as u32,
+                balls: play["count"]["balls"].as_u64().unwrap_or(0) as u8,
+                strikes: play["count"]["strikes"].as_u64().unwrap_or(0) as u8,
+                outcome: play["result"]["eventType"].as_str().unwrap_or("unknown").to_string(),
+                before,
+                after: state,
+                runs_scored,
+            });
+        }
+
+        Ok(GamePlays {
+            game_pk,
+            plate_appearances,
+        })
+    }
+
+    fn file_path(game_pk: u32) -> String {
+        format!("data/plays/{}.csv", game_pk)
+    }
+
+    /// Load the game's plays from disk, if they have been stored.
+    pub fn load(game_pk: u32) -> Option<Self> {
+        let lines = std::fs::read_to_string(GamePlays::file_path(game_pk)).ok()?;
+        let plate_appearances = lines
+            .lines()
+            .skip(1)
+            .map(PlateAppearance::from_line)
+            .collect::<Option<Vec<PlateAppearance>>>()?;
+
+        Some(GamePlays {
+            game_pk,
+            plate_appearances,
+        })
+    }
+
+    fn save(&self) -> Result<(), String> {
+        std::fs::create_dir_all("data/plays")
+            .map_err(|e| format!("Failed to create directories: {}", e))?;
+
+        let mut lines = vec![HEADER.to_string()];
+        lines.extend(self.iter().map(PlateAppearance::to_line));
+
+        let file_path = GamePlays::file_path(self.game_pk);
+        std::fs::write(&file_path, lines.join("\n"))
+            .map_err(|e| format!("Failed to write file {}: {}", file_path, e))?;
+
+        Ok(())
+    }
+}
+
+impl Game {
+    /// Load the stored plays of this game, if it has a game ID and its plays have been ingested.
+    pub fn plays(&self) -> Option<GamePlays> {
+        GamePlays::load(self.setting.game_pk?)
+    }
+}
+
+/// Iterate over the plays of every game that has been ingested, in no particular order.
+pub fn iter_stored() -> impl Iterator<Item = GamePlays> {
+    std::fs::read_dir("data/plays")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|file| {
+            let game_pk = file.path().file_stem()?.to_str()?.parse().ok()?;
+            GamePlays::load(game_pk)
+        })
+}
+
+/// Fetch and store the plays of every given game that has a game ID and hasn't been ingested yet.
+pub async fn ingest(games: &[Game], show_errors: bool) {
+    let game_pks: Vec<u32> = games.iter().filter_map(|game| game.setting.game_pk).collect();
+    for game_pk in game_pks.into_iter().progress() {
+        if let Err(e) = GamePlays::get(game_pk).await {
+            if show_errors {
+                eprintln!("{}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runner(id: u64, start: Option<&str>, end: Option<&str>, is_out: bool) -> serde_json::Value {
+        serde_json::json!({
+            "movement": { "start": start, "end": end, "isOut": is_out },
+            "details": { "runner": { "id": id } },
+        })
+    }
+
+    #[test]
+    fn batter_listed_before_the_runner_ahead_of_them() {
+        // a single with a runner on first moving to second
+        let runners = [runner(1, None, Some("1B"), false), runner(2, Some("1B"), Some("2B"), false)];
+        assert_eq!(move_runners(1, &runners), (3, 0));
+    }
+
+    #[test]
+    fn runner_with_several_movements() {
+        // a runner steals second and scores on a double, with the segments listed out of order
+        let runners = [
+            runner(2, Some("2B"), Some("score"), false),
+            runner(1, None, Some("2B"), false),
+            runner(2, Some("1B"), Some("2B"), false),
+        ];
+        assert_eq!(move_runners(1, &runners), (2, 1));
+    }
+
+    #[test]
+    fn runner_thrown_out() {
+        let runners = [runner(1, None, Some("1B"), false), runner(2, Some("1B"), None, true)];
+        assert_eq!(move_runners(1, &runners), (1, 0));
+    }
+}
@@ -0,0 +1,282 @@
+use serde::{Deserialize, Serialize};
+
+use crate::markov::MAX_RUNS;
+use crate::model::Game;
+use crate::models::count::home_win_probability;
+use crate::simulation::SimulationResult;
+
+/// The total runs lines that predictions are priced at by default.
+pub const DEFAULT_TOTAL_LINES: [f64; 5] = [6.5, 7.5, 8.5, 9.5, 10.5];
+
+/// The first five innings total runs lines that predictions are priced at by default.
+pub const DEFAULT_FIRST_FIVE_LINES: [f64; 3] = [3.5, 4.5, 5.5];
+
+/// The usual run line: the favorite gives a run and a half.
+pub const RUN_LINE: f64 = 1.5;
+
+/// What a model says about a game before it's played. Models fill in what they can: a rating system only gives a
+/// win probability, a regression only expected runs, and a count model or simulation the whole score distribution,
+/// from which the probabilities of the betting markets follow.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Prediction {
+    pub expected_home_runs: Option<f64>,
+    pub expected_away_runs: Option<f64>,
+    pub home_win_probability: Option<f64>,
+    /// The probability of each final score, indexed by `[home runs][away runs]`. This is too big to store with every
+    /// prediction, so only the distributions of its totals and margins are saved.
+    #[serde(skip)]
+    pub score_probabilities: Option<Vec<Vec<f64>>>,
+    /// The probability of each number of total runs in the game.
+    pub total_runs_probabilities: Option<Vec<f64>>,
+    /// The probability of each margin of the home team's runs over the away team's, indexed by the margin plus
+    /// [`MAX_RUNS`]. A tied score is settled in extra innings, by a run either way with equal chances.
+    pub margin_probabilities: Option<Vec<f64>>,
+    /// The probability of each number of total runs after five innings.
+    pub first_five_total_runs_probabilities: Option<Vec<f64>>,
+}
+
+/// The probabilities of the total going over, under or landing on a line. Only whole-number lines can push.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct OverUnder {
+    pub line: f64,
+    pub over: f64,
+    pub under: f64,
+    pub push: f64,
+}
+
+/// The probabilities of each side of a run line covering, where the home team gives `spread` runs (so a negative
+/// spread makes the home team the underdog).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RunLine {
+    pub spread: f64,
+    pub home: f64,
+    pub away: f64,
+    pub push: f64,
+}
+
+/// The probability of each total of a score grid. A final score can't be a tie, so for a final score grid
+/// (`final_score`) ties are moved to the total of a one-run extra-inning win, as in [`margin_probabilities`].
+fn total_runs_probabilities(grid: &[Vec<f64>], final_score: bool) -> Vec<f64> {
+    let mut probabilities = vec![0.0; 2 * MAX_RUNS + 1];
+    for (home_runs, row) in grid.iter().enumerate() {
+        for (away_runs, probability) in row.iter().enumerate() {
+            let mut total = home_runs + away_runs;
+            if final_score && home_runs == away_runs {
+                total += 1;
+            }
+            probabilities[total.min(2 * MAX_RUNS)] += probability;
+        }
+    }
+
+    probabilities
+}
+
+/// The probability of each margin of a score grid, indexed by the margin plus [`MAX_RUNS`], with ties split between a
+/// one-run win for either team.
+fn margin_probabilities(grid: &[Vec<f64>]) -> Vec<f64> {
+    let mut probabilities = vec![0.0; 2 * MAX_RUNS + 1];
+    for (home_runs, row) in grid.iter().enumerate() {
+        for (away_runs, probability) in row.iter().enumerate() {
+            if home_runs == away_runs {
+                probabilities[MAX_RUNS + 1] += probability / 2.0;
+                probabilities[MAX_RUNS - 1] += probability / 2.0;
+            } else {
+                probabilities[MAX_RUNS + home_runs - away_runs] += probability;
+            }
+        }
+    }
+
+    probabilities
+}
+
+/// The over/under probabilities of a line given the distribution of the total.
+fn over_under(distribution: &[f64], line: f64) -> OverUnder {
+    let mut over_under = OverUnder { line, over: 0.0, under: 0.0, push: 0.0 };
+    for (total, probability) in distribution.iter().enumerate() {
+        let total = total as f64;
+        if total > line {
+            over_under.over += probability;
+        } else if total < line {
+            over_under.under += probability;
+        } else {
+            over_under.push += probability;
+        }
+    }
+
+    over_under
+}
+
+impl Prediction {
+    pub fn from_expected_runs(home_runs: f64, away_runs: f64) -> Self {
+        Prediction {
+            expected_home_runs: Some(home_runs),
+            expected_away_runs: Some(away_runs),
+            ..Prediction::default()
+        }
+    }
+
+    pub fn from_home_win_probability(probability: f64) -> Self {
+        Prediction {
+            home_win_probability: Some(probability),
+            ..Prediction::default()
+        }
+    }
+
+    /// Everything that follows from a score distribution indexed by `[home runs][away runs]` (up to [`MAX_RUNS`]).
+    pub fn from_score_probabilities(grid: Vec<Vec<f64>>) -> Self {
+        let mut expected_home_runs = 0.0;
+        let mut expected_away_runs = 0.0;
+        for (home_runs, row) in grid.iter().enumerate() {
+            for (away_runs, probability) in row.iter().enumerate() {
+                expected_home_runs += home_runs as f64 * probability;
+                expected_away_runs += away_runs as f64 * probability;
+            }
+        }
+
+        Prediction {
+            expected_home_runs: Some(expected_home_runs),
+            expected_away_runs: Some(expected_away_runs),
+            home_win_probability: Some(home_win_probability(&grid)),
+            total_runs_probabilities: Some(total_runs_probabilities(&grid, true)),
+            margin_probabilities: Some(margin_probabilities(&grid)),
+            score_probabilities: Some(grid),
+            first_five_total_runs_probabilities: None,
+        }
+    }
+
+    /// Everything that follows from simulating the game, including the score after five innings.
+    pub fn from_simulation(result: &SimulationResult) -> Self {
+        // the simulated means and win probability aren't cut off at the grid's maximum score
+        Prediction {
+            expected_home_runs: Some(result.expected_home_runs),
+            expected_away_runs: Some(result.expected_away_runs),
+            home_win_probability: Some(result.home_win_probability),
+            first_five_total_runs_probabilities: Some(total_runs_probabilities(&result.first_five_score_probabilities, false)),
+            ..Prediction::from_score_probabilities(result.score_probabilities.clone())
+        }
+    }
+
+    /// The probabilities of the game's total runs going over or under a line.
+    pub fn total_runs(&self, line: f64) -> Option<OverUnder> {
+        self.total_runs_probabilities.as_ref().map(|distribution| over_under(distribution, line))
+    }
+
+    /// The probabilities of the total runs after five innings going over or under a line.
+    pub fn first_five_total_runs(&self, line: f64) -> Option<OverUnder> {
+        self.first_five_total_runs_probabilities.as_ref().map(|distribution| over_under(distribution, line))
+    }
+
+    /// The probabilities of each side covering a run line where the home team gives `spread` runs.
+    pub fn run_line(&self, spread: f64) -> Option<RunLine> {
+        let distribution = self.margin_probabilities.as_ref()?;
+        let mut run_line = RunLine { spread, home: 0.0, away: 0.0, push: 0.0 };
+        for (index, probability) in distribution.iter().enumerate() {
+            let margin = index as f64 - MAX_RUNS as f64;
+            if margin > spread {
+                run_line.home += probability;
+            } else if margin < spread {
+                run_line.away += probability;
+            } else {
+                run_line.push += probability;
+            }
+        }
+
+        Some(run_line)
+    }
+}
+
+/// A model that can be refit on the games before a date and then predict the games on it, so that it can be
+/// backtested by [`crate::backtest::walk_forward`].
+pub trait Predictor {
+    /// The model and its settings, recorded with each of its predictions.
+    fn version(&self) -> String;
+
+    /// Fit the model to the given games, in chronological order, replacing any earlier fit. Each call is given the
+    /// games of the previous call and those played since.
+    fn train(&mut self, games: &[&Game]) -> Result<(), String>;
+
+    /// Predict a game played after every game the model was trained on. Must only be called after `train` succeeds.
+    fn predict(&self, game: &Game) -> Prediction;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-12, "{} != {}", actual, expected);
+    }
+
+    /// Home 2-0 (0.4), 1-1 (0.2), 0-3 (0.3) and 3-1 (0.1).
+    fn prediction() -> Prediction {
+        let mut grid = vec![vec![0.0; 4]; 4];
+        grid[2][0] = 0.4;
+        grid[1][1] = 0.2;
+        grid[0][3] = 0.3;
+        grid[3][1] = 0.1;
+
+        Prediction::from_score_probabilities(grid)
+    }
+
+    #[test]
+    fn over_under() {
+        let prediction = prediction();
+
+        // the 1-1 tie is won by a run in extra innings, for a total of 3
+        let half_line = prediction.total_runs(2.5).unwrap();
+        assert_close(half_line.over, 0.6);
+        assert_close(half_line.under, 0.4);
+        assert_close(half_line.push, 0.0);
+
+        let whole_line = prediction.total_runs(3.0).unwrap();
+        assert_close(whole_line.over, 0.1);
+        assert_close(whole_line.under, 0.4);
+        assert_close(whole_line.push, 0.5);
+    }
+
+    #[test]
+    fn run_line() {
+        let prediction = prediction();
+
+        // the tie goes to a one-run win either way
+        let home_favorite = prediction.run_line(RUN_LINE).unwrap();
+        assert_close(home_favorite.home, 0.5);
+        assert_close(home_favorite.away, 0.5);
+
+        let away_favorite = prediction.run_line(-RUN_LINE).unwrap();
+        assert_close(away_favorite.home, 0.7);
+        assert_close(away_favorite.away, 0.3);
+        assert_close(away_favorite.push, 0.0);
+
+        assert_close(prediction.home_win_probability.unwrap(), 0.6);
+        assert_close(prediction.expected_home_runs.unwrap(), 1.3);
+    }
+
+    #[test]
+    fn ties_go_to_extra_innings_in_totals_and_margins() {
+        // 4-4 (0.5) and 5-3 (0.5)
+        let mut grid = vec![vec![0.0; 6]; 6];
+        grid[4][4] = 0.5;
+        grid[5][3] = 0.5;
+        let prediction = Prediction::from_score_probabilities(grid);
+
+        // the tie ends 5-4 or 4-5: a one-run margin and a total of 9, never 8
+        let totals = prediction.total_runs_probabilities.as_ref().unwrap();
+        assert_close(totals[8], 0.5);
+        assert_close(totals[9], 0.5);
+        let margins = prediction.margin_probabilities.as_ref().unwrap();
+        assert_close(margins[MAX_RUNS + 1] + margins[MAX_RUNS - 1], 0.5);
+        assert_close(margins[MAX_RUNS], 0.0);
+
+        let total = prediction.total_runs(8.0).unwrap();
+        assert_close(total.push, 0.5);
+        assert_close(total.over, 0.5);
+    }
+
+    #[test]
+    fn markets_need_a_distribution() {
+        let prediction = Prediction::from_expected_runs(4.5, 4.0);
+        assert!(prediction.total_runs(8.5).is_none());
+        assert!(prediction.run_line(RUN_LINE).is_none());
+    }
+}
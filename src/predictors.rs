@@ -0,0 +1,257 @@
+use crate::bradley_terry::BradleyTerry;
+use crate::event_rates::EventRates;
+use crate::model::Game;
+use crate::models::boosting::{BoostingParams, GradientBoosting};
+use crate::models::count::CountModel;
+use crate::models::dataset::Dataset;
+use crate::models::features::feature_row;
+use crate::models::linear::LinearModel;
+use crate::models::Regressor;
+use crate::prediction::{Prediction, Predictor};
+use crate::ratings::Elo;
+use crate::simulation::GameSimulator;
+
+/// The number of most recent games that the season-level models are fit to: about one season's worth.
+pub const DEFAULT_WINDOW: usize = 2430;
+
+/// The share of the training games, the latest ones, held out to stop boosting early.
+const VALIDATION_FRACTION: f64 = 0.2;
+
+/// The last `window` games.
+fn recent<'a, 'b>(games: &'b [&'a Game], window: usize) -> &'b [&'a Game] {
+    &games[games.len().saturating_sub(window)..]
+}
+
+/// Predicts the winner from Elo ratings updated through every training game.
+#[derive(Debug, Clone, Default)]
+pub struct EloPredictor {
+    elo: Elo,
+    games_seen: usize,
+}
+
+impl Predictor for EloPredictor {
+    fn version(&self) -> String {
+        "elo".to_string()
+    }
+
+    /// Only the games since the last call are replayed.
+    fn train(&mut self, games: &[&Game]) -> Result<(), String> {
+        if games.len() < self.games_seen {
+            *self = EloPredictor::default();
+        }
+        for game in &games[self.games_seen..] {
+            self.elo.update(game);
+        }
+        self.games_seen = games.len();
+
+        Ok(())
+    }
+
+    fn predict(&self, game: &Game) -> Prediction {
+        let mut elo = self.elo.clone();
+        elo.start_game(game);
+        Prediction::from_home_win_probability(elo.home_win_probability(game.home_team.team_id, game.away_team.team_id))
+    }
+}
+
+/// Predicts each team's runs from the Kalman filter strengths that [`crate::history::derive_features`] stores with
+/// the game, which only depend on earlier games, so there's nothing to train.
+#[derive(Debug, Clone, Default)]
+pub struct StrengthPredictor;
+
+impl Predictor for StrengthPredictor {
+    fn version(&self) -> String {
+        "strength".to_string()
+    }
+
+    fn train(&mut self, _games: &[&Game]) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn predict(&self, game: &Game) -> Prediction {
+        let expected_runs = |team: &crate::model::Team| team.ratings.as_ref().and_then(|ratings| ratings.expected_runs).map(|runs| runs as f64);
+
+        Prediction {
+            expected_home_runs: expected_runs(&game.home_team),
+            expected_away_runs: expected_runs(&game.away_team),
+            ..Prediction::default()
+        }
+    }
+}
+
+/// Predicts the winner from a Bradley–Terry model of the most recent games.
+#[derive(Debug, Clone)]
+pub struct BradleyTerryPredictor {
+    pub window: usize,
+    model: Option<BradleyTerry>,
+}
+
+impl BradleyTerryPredictor {
+    pub fn new(window: usize) -> Self {
+        BradleyTerryPredictor { window, model: None }
+    }
+}
+
+impl Predictor for BradleyTerryPredictor {
+    fn version(&self) -> String {
+        format!("bradley-terry(window={})", self.window)
+    }
+
+    fn train(&mut self, games: &[&Game]) -> Result<(), String> {
+        self.model = Some(BradleyTerry::fit(recent(games, self.window).iter().copied()).ok_or("Failed to fit the Bradley–Terry model")?);
+        Ok(())
+    }
+
+    fn predict(&self, game: &Game) -> Prediction {
+        let model = self.model.as_ref().expect("predict called before train");
+        Prediction::from_home_win_probability(model.home_win_probability(game.home_team.team_id, game.away_team.team_id))
+    }
+}
+
+/// The score distribution a [`CountModel`] predicts with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountDistribution {
+    NegativeBinomial,
+    BivariatePoisson,
+}
+
+/// Predicts the score distribution from a [`CountModel`] of the most recent games.
+#[derive(Debug, Clone)]
+pub struct CountPredictor {
+    pub distribution: CountDistribution,
+    pub window: usize,
+    model: Option<CountModel>,
+}
+
+impl CountPredictor {
+    pub fn new(distribution: CountDistribution, window: usize) -> Self {
+        CountPredictor { distribution, window, model: None }
+    }
+}
+
+impl Predictor for CountPredictor {
+    fn version(&self) -> String {
+        let distribution = match self.distribution {
+            CountDistribution::NegativeBinomial => "negative-binomial",
+            CountDistribution::BivariatePoisson => "bivariate-poisson",
+        };
+        format!("{}(window={})", distribution, self.window)
+    }
+
+    fn train(&mut self, games: &[&Game]) -> Result<(), String> {
+        self.model = Some(CountModel::fit(recent(games, self.window).iter().copied())?);
+        Ok(())
+    }
+
+    fn predict(&self, game: &Game) -> Prediction {
+        let model = self.model.as_ref().expect("predict called before train");
+        let grid = match self.distribution {
+            CountDistribution::NegativeBinomial => model.negative_binomial_grid(game),
+            CountDistribution::BivariatePoisson => model.bivariate_poisson_grid(game),
+        };
+
+        Prediction::from_score_probabilities(grid)
+    }
+}
+
+/// Predicts the score distribution by simulating the game against the league rates of the most recent games.
+#[derive(Debug, Clone)]
+pub struct SimulationPredictor {
+    pub simulations: u32,
+    pub seed: u64,
+    pub window: usize,
+    league: Option<EventRates>,
+}
+
+impl SimulationPredictor {
+    pub fn new(simulations: u32, seed: u64, window: usize) -> Self {
+        SimulationPredictor { simulations, seed, window, league: None }
+    }
+}
+
+impl Predictor for SimulationPredictor {
+    fn version(&self) -> String {
+        format!("simulation(simulations={}, seed={}, window={})", self.simulations, self.seed, self.window)
+    }
+
+    fn train(&mut self, games: &[&Game]) -> Result<(), String> {
+        self.league = Some(EventRates::league(recent(games, self.window).iter().copied()));
+        Ok(())
+    }
+
+    fn predict(&self, game: &Game) -> Prediction {
+        let league = self.league.as_ref().expect("predict called before train");
+        Prediction::from_simulation(&GameSimulator::for_game(game, league).simulate(self.simulations, self.seed))
+    }
+}
+
+/// The kinds of regression that a [`RegressorPredictor`] fits.
+#[derive(Debug, Clone)]
+pub enum RegressorKind {
+    Ridge { alpha: f64 },
+    Poisson { alpha: f64 },
+    Boosting(BoostingParams),
+}
+
+/// Predicts each team's runs with a regression on the stored features of every training game.
+pub struct RegressorPredictor {
+    pub kind: RegressorKind,
+    /// The training games' features, kept between calls so that only new games need converting.
+    dataset: Dataset,
+    model: Option<Box<dyn Regressor>>,
+}
+
+impl RegressorPredictor {
+    pub fn new(kind: RegressorKind, feature_names: &[String]) -> Self {
+        RegressorPredictor {
+            kind,
+            dataset: Dataset::from_games([], feature_names),
+            model: None,
+        }
+    }
+}
+
+impl Predictor for RegressorPredictor {
+    fn version(&self) -> String {
+        let features = self.dataset.feature_names.len();
+        match &self.kind {
+            RegressorKind::Ridge { alpha } => format!("ridge(alpha={}, features={})", alpha, features),
+            RegressorKind::Poisson { alpha } => format!("poisson(alpha={}, features={})", alpha, features),
+            RegressorKind::Boosting(params) => format!(
+                "boost(loss={:?}, learning_rate={}, max_depth={}, rounds={}, seed={}, features={})",
+                params.loss, params.learning_rate, params.max_depth, params.num_boost_round, params.seed, features,
+            ),
+        }
+    }
+
+    fn train(&mut self, games: &[&Game]) -> Result<(), String> {
+        if games.len() < self.dataset.len() {
+            self.dataset = Dataset::from_games([], &self.dataset.feature_names);
+        }
+        let new_games = Dataset::from_games(games[self.dataset.len()..].iter().copied(), &self.dataset.feature_names);
+        self.dataset.features.extend(new_games.features);
+        self.dataset.targets.extend(new_games.targets);
+
+        self.model = Some(match &self.kind {
+            RegressorKind::Ridge { alpha } => Box::new(LinearModel::fit_ridge(&self.dataset, *alpha)?),
+            RegressorKind::Poisson { alpha } => Box::new(LinearModel::fit_poisson(&self.dataset, *alpha)?),
+            RegressorKind::Boosting(params) => {
+                // hold out the latest games rather than a random sample, so nothing is validated on earlier games
+                let train_size = self.dataset.len() - (self.dataset.len() as f64 * VALIDATION_FRACTION).ceil() as usize;
+                let indices: Vec<usize> = (0..self.dataset.len()).collect();
+                let train = self.dataset.subset(&indices[..train_size]);
+                let validation = self.dataset.subset(&indices[train_size..]);
+                Box::new(GradientBoosting::fit(&train, &validation, params)?)
+            },
+        });
+
+        Ok(())
+    }
+
+    fn predict(&self, game: &Game) -> Prediction {
+        let model = self.model.as_ref().expect("predict called before train");
+        let prediction = model.predict(&feature_row(game, model.feature_names()));
+
+        Prediction::from_expected_runs(prediction[0], prediction[1])
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::Game;
+
+const INITIAL_ELO: f64 = 1500.0;
+
+/// The most an Elo rating moves after a one-run game against an even opponent, halved.
+const ELO_K: f64 = 4.0;
+
+/// The Elo points added to the home team's rating when predicting a game.
+const ELO_HOME_FIELD_ADVANTAGE: f64 = 24.0;
+
+/// The share of the distance to the league average that each Elo rating loses between seasons.
+const ELO_SEASON_REGRESSION: f64 = 1.0 / 3.0;
+
+/// A team's ratings going into a game, from the games before it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ratings {
+    pub elo: Option<f32>,
+    /// Runs per game the team scores above average, from [`crate::strength::StrengthFilter`].
+    pub offense: Option<f32>,
+    /// Runs per game the team allows above average (so lower is better).
+    pub defense: Option<f32>,
+    /// The runs the team is expected to score in the game, given its offense and the opponent's defense.
+    pub expected_runs: Option<f32>,
+    /// The standard deviation of `expected_runs` from the uncertainty of the strengths.
+    pub expected_runs_uncertainty: Option<f32>,
+    /// Opponent-adjusted runs per game the team scores above average so far this season, from [`crate::massey`].
+    pub massey_offense: Option<f32>,
+    /// Opponent-adjusted runs per game the team allows above average so far this season.
+    pub massey_defense: Option<f32>,
+    /// The team's strength in log odds so far this season, from [`crate::bradley_terry`].
+    pub bradley_terry: Option<f32>,
+}
+
+/// Elo ratings of every team, updated game by game.
+///
+/// A win moves the ratings by more the bigger its margin, but by less when the favorite wins, so that good teams'
+/// ratings don't inflate from running up scores.
+#[derive(Debug, Clone, Default)]
+pub struct Elo {
+    ratings: HashMap<u8, f64>,
+    season: Option<u16>,
+}
+
+impl Elo {
+    pub fn rating(&self, team_id: u8) -> f64 {
+        self.ratings.get(&team_id).copied().unwrap_or(INITIAL_ELO)
+    }
+
+    /// The probability that the home team beats the away team.
+    pub fn home_win_probability(&self, home_team_id: u8, away_team_id: u8) -> f64 {
+        let difference = self.rating(home_team_id) + ELO_HOME_FIELD_ADVANTAGE - self.rating(away_team_id);
+        1.0 / (1.0 + 10f64.powf(-difference / 400.0))
+    }
+
+    /// Regress every rating toward the league average if the game starts a new season.
+    pub fn start_game(&mut self, game: &Game) {
+        let season = game.setting.date.year;
+        if self.season.is_some_and(|previous| previous != season) {
+            let mean = self.ratings.values().sum::<f64>() / self.ratings.len().max(1) as f64;
+            for rating in self.ratings.values_mut() {
+                *rating -= ELO_SEASON_REGRESSION * (*rating - mean);
+            }
+        }
+        self.season = Some(season);
+    }
+
+    /// Update both teams' ratings from the game's result.
+    pub fn update(&mut self, game: &Game) {
+        self.start_game(game);
+
+        let home = game.home_team.team_id;
+        let away = game.away_team.team_id;
+        let margin = game.result.home_team_score as f64 - game.result.away_team_score as f64;
+        if margin == 0.0 {
+            return;
+        }
+
+        let expected = self.home_win_probability(home, away);
+        let actual = if margin > 0.0 { 1.0 } else { 0.0 };
+
+        let winner_advantage = (self.rating(home) + ELO_HOME_FIELD_ADVANTAGE - self.rating(away)) * margin.signum();
+        let multiplier = (margin.abs() + 1.0).ln() * 2.2 / (winner_advantage * 0.001 + 2.2);
+
+        let change = ELO_K * multiplier * (actual - expected);
+        *self.ratings.entry(home).or_insert(INITIAL_ELO) += change;
+        *self.ratings.entry(away).or_insert(INITIAL_ELO) -= change;
+    }
+}
+
+/// Set both teams' Elo ratings going into every game.
+///
+/// `games` must be in chronological order.
+pub fn assign_elo(games: &mut [Game]) {
+    let mut elo = Elo::default();
+    for game in games.iter_mut() {
+        elo.start_game(game);
+        for team in [&mut game.home_team, &mut game.away_team] {
+            team.ratings.get_or_insert_with(Ratings::default).elo = Some(elo.rating(team.team_id) as f32);
+        }
+        elo.update(game);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elo_exchange_sums_to_zero() {
+        let mut games = vec![
+            Game::with_score("2024-04-01", 1, 2, 5, 2),
+            Game::with_score("2024-04-02", 2, 3, 1, 4),
+            Game::with_score("2024-04-03", 3, 1, 6, 5),
+            Game::with_score("2024-04-04", 1, 3, 3, 3),
+        ];
+        assign_elo(&mut games);
+
+        let mut elo = Elo::default();
+        for game in &games[..3] {
+            elo.update(game);
+            // whatever moves between the two teams, the league's total is unchanged
+            let total: f64 = [1, 2, 3].iter().map(|team_id| elo.rating(*team_id)).sum();
+            assert!((total - 3.0 * INITIAL_ELO).abs() < 1e-9, "total {}", total);
+        }
+        assert!(elo.rating(1) > INITIAL_ELO && elo.rating(2) < INITIAL_ELO);
+
+        // the last game's ratings come from the three games before it
+        let target = &games[3];
+        assert_eq!(target.home_team.ratings.as_ref().unwrap().elo, Some(elo.rating(1) as f32));
+        assert_eq!(target.away_team.ratings.as_ref().unwrap().elo, Some(elo.rating(3) as f32));
+    }
+}
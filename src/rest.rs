@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::history;
+use crate::model::{Date, Game};
+use crate::venue::Venue;
+
+const EARTH_RADIUS_KM: f32 = 6371.0;
+
+/// How rested a team is going into a game, and how far it has traveled since its last one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rest {
+    /// Days since the team's previous game (0 for the second game of a doubleheader), if it was in the same season.
+    pub days_since_last_game: Option<u16>,
+    /// Games the team played in the 7 calendar days ending on the game's date (the first game of a doubleheader counts),
+    /// not including this one.
+    pub games_last_7_days: u8,
+    /// Games the team played in the 14 calendar days ending on the game's date, not including this one.
+    pub games_last_14_days: u8,
+    /// The number of road games in a row the team has played, including this one (0 for a home game).
+    pub consecutive_road_games: u8,
+    /// Hours the team's clock has moved since its previous game (positive when traveling east).
+    pub timezone_shift: Option<i8>,
+    /// Great-circle distance between the venues of the team's previous game and this one, in kilometers.
+    pub travel_distance: Option<f32>,
+}
+
+/// The great-circle distance between two venues, if both have coordinates.
+fn distance_km(from: &Venue, to: &Venue) -> Option<f32> {
+    let (from_latitude, from_longitude) = (from.latitude?.to_radians(), from.longitude?.to_radians());
+    let (to_latitude, to_longitude) = (to.latitude?.to_radians(), to.longitude?.to_radians());
+
+    let a = ((to_latitude - from_latitude) / 2.0).sin().powi(2)
+        + from_latitude.cos() * to_latitude.cos() * ((to_longitude - from_longitude) / 2.0).sin().powi(2);
+
+    Some(2.0 * EARTH_RADIUS_KM * a.sqrt().asin())
+}
+
+struct PlayedGame<'a> {
+    date: Date,
+    venue: Option<&'a Venue>,
+    consecutive_road_games: u8,
+}
+
+/// Set the rest and travel features of both teams in every game from the games before it.
+///
+/// `games` must be in chronological order.
+pub fn assign_rest(games: &mut [Game]) {
+    let home_venues = history::home_venues(games);
+
+    let mut all_rest = Vec::with_capacity(games.len());
+    let mut played: HashMap<u8, Vec<PlayedGame>> = HashMap::new();
+    for game in games.iter() {
+        let date = game.setting.date;
+        let venue = history::venue_of(game, &home_venues);
+
+        let mut rest_of = |team_id: u8, home: bool| {
+            let team_games = played.entry(team_id).or_default();
+            let previous = team_games.last().filter(|previous| previous.date.year == date.year);
+            let games_since = |days: i32| team_games.iter().rev().take_while(|played| date.days_since(&played.date) < days).count() as u8;
+
+            let consecutive_road_games = match (home, previous) {
+                (true, _) => 0,
+                (false, Some(previous)) => previous.consecutive_road_games + 1,
+                (false, None) => 1,
+            };
+            let rest = Rest {
+                days_since_last_game: previous.map(|previous| date.days_since(&previous.date) as u16),
+                games_last_7_days: games_since(7),
+                games_last_14_days: games_since(14),
+                consecutive_road_games,
+                timezone_shift: previous
+                    .and_then(|previous| Some(venue?.utc_offset? - previous.venue?.utc_offset?)),
+                travel_distance: previous
+                    .and_then(|previous| distance_km(previous.venue?, venue?)),
+            };
+
+            team_games.push(PlayedGame {
+                date,
+                venue,
+                consecutive_road_games,
+            });
+            rest
+        };
+
+        let home_rest = rest_of(game.home_team.team_id, true);
+        let away_rest = rest_of(game.away_team.team_id, false);
+        all_rest.push((home_rest, away_rest));
+    }
+
+    for (game, (home_rest, away_rest)) in games.iter_mut().zip(all_rest) {
+        game.home_team.rest = Some(home_rest);
+        game.away_team.rest = Some(away_rest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rest_counts_the_calendar_days_before_the_game() {
+        let mut games = vec![
+            Game::with_score("2024-04-01", 1, 2, 5, 2),
+            Game::with_score("2024-04-03", 2, 1, 3, 4),
+            Game::with_score("2024-04-04", 3, 1, 1, 0),
+            Game::with_score("2024-04-08", 2, 1, 6, 2),
+        ];
+        assign_rest(&mut games);
+
+        let first = games[0].home_team.rest.as_ref().unwrap();
+        assert_eq!((first.days_since_last_game, first.games_last_7_days, first.consecutive_road_games), (None, 0, 0));
+
+        // April 1st is 7 days before, so it's outside the 7 days ending on the game's date but inside the 14
+        let rest = games[3].away_team.rest.as_ref().unwrap();
+        assert_eq!(rest.days_since_last_game, Some(4));
+        assert_eq!(rest.games_last_7_days, 2);
+        assert_eq!(rest.games_last_14_days, 3);
+        assert_eq!(rest.consecutive_road_games, 3);
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use crate::event_rates::EventRates;
+use crate::markov::HalfInningSimulator;
+use crate::model::Game;
+use crate::plays::{BaseOutState, GamePlays};
+
+/// The expected runs from each of the 24 base-out states until the end of the half-inning.
+#[derive(Debug, Clone)]
+pub struct RunExpectancy {
+    /// Indexed by [`BaseOutState::index`].
+    pub runs: [f64; 24],
+    /// The number of plate appearances each value was estimated from (all zero for a Markov estimate).
+    pub plate_appearances: [u32; 24],
+}
+
+impl RunExpectancy {
+    pub fn get(&self, state: BaseOutState) -> f64 {
+        state.index().map(|index| self.runs[index]).unwrap_or(0.0)
+    }
+
+    /// Estimate the matrix from stored play-by-play: the average runs scored from each state until the end of the
+    /// half-inning. Half-innings that didn't reach three outs (walk-offs) are left out.
+    pub fn from_plays<'a>(games: impl Iterator<Item = &'a GamePlays>) -> Self {
+        let mut total_runs = [0.0; 24];
+        let mut plate_appearances = [0; 24];
+        for plays in games {
+            let plate_appearances_in_game = &plays.plate_appearances;
+            let mut start = 0;
+            while start < plate_appearances_in_game.len() {
+                let first = &plate_appearances_in_game[start];
+                let end = start + plate_appearances_in_game[start..]
+                    .iter()
+                    .take_while(|plate_appearance| (plate_appearance.inning, plate_appearance.half) == (first.inning, first.half))
+                    .count();
+                let half_inning = &plate_appearances_in_game[start..end];
+                start = end;
+
+                if half_inning.last().is_some_and(|last| last.after.outs < 3) {
+                    continue;
+                }
+                let mut runs_to_come: u32 = half_inning.iter().map(|plate_appearance| plate_appearance.runs_scored as u32).sum();
+                for plate_appearance in half_inning {
+                    if let Some(index) = plate_appearance.before.index() {
+                        total_runs[index] += runs_to_come as f64;
+                        plate_appearances[index] += 1;
+                    }
+                    runs_to_come -= plate_appearance.runs_scored as u32;
+                }
+            }
+        }
+
+        let mut runs = [0.0; 24];
+        for index in 0..24 {
+            if plate_appearances[index] > 0 {
+                runs[index] = total_runs[index] / plate_appearances[index] as f64;
+            }
+        }
+
+        RunExpectancy { runs, plate_appearances }
+    }
+
+    /// Compute the matrix implied by the given event rates, using the Markov half-inning simulator.
+    pub fn from_event_rates(rates: &EventRates) -> Self {
+        let simulator = HalfInningSimulator::new(*rates);
+
+        let mut runs = [0.0; 24];
+        for (index, expected_runs) in runs.iter_mut().enumerate() {
+            let state = BaseOutState {
+                bases: (index % 8) as u8,
+                outs: (index / 8) as u8,
+            };
+            *expected_runs = simulator.expected_runs_from(state);
+        }
+
+        RunExpectancy { runs, plate_appearances: [0; 24] }
+    }
+
+    /// Compute the matrix for each season of the given games, from their stored play-by-play where any has been
+    /// ingested, and otherwise from the Markov simulator with the season's league event rates.
+    pub fn by_season(games: &[Game]) -> BTreeMap<u16, RunExpectancy> {
+        let mut seasons: BTreeMap<u16, Vec<&Game>> = BTreeMap::new();
+        for game in games {
+            seasons.entry(game.setting.date.year).or_default().push(game);
+        }
+
+        seasons
+            .into_iter()
+            .map(|(season, season_games)| {
+                let plays: Vec<GamePlays> = season_games.iter().filter_map(|game| game.plays()).collect();
+                let run_expectancy = if plays.is_empty() {
+                    RunExpectancy::from_event_rates(&EventRates::league(season_games))
+                } else {
+                    RunExpectancy::from_plays(plays.iter())
+                };
+                (season, run_expectancy)
+            })
+            .collect()
+    }
+}
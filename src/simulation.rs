@@ -0,0 +1,257 @@
+use serde::Serialize;
+
+use crate::event_rates::{EventRates, EVENTS};
+use crate::linescore::DEFAULT_SCHEDULED_INNINGS;
+use crate::markov::{transitions, Transition, MAX_RUNS};
+use crate::model::Game;
+use crate::plays::BaseOutState;
+use crate::rng::Rng;
+
+/// The first season in which extra innings started with a runner on second.
+const FIRST_EXTRA_INNING_RUNNER_SEASON: u16 = 2020;
+
+/// The inning after which a simulated game still tied is abandoned.
+const MAX_INNINGS: u8 = 30;
+
+/// The quantiles of total runs reported by [`SimulationResult`].
+const TOTAL_RUNS_QUANTILES: [f64; 5] = [0.05, 0.25, 0.5, 0.75, 0.95];
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Quantile {
+    pub quantile: f64,
+    pub runs: u8,
+}
+
+/// The outcome of simulating a game many times.
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationResult {
+    pub simulations: u32,
+    /// The probability of each final score, indexed by `[home runs][away runs]` (capped at [`MAX_RUNS`]).
+    pub score_probabilities: Vec<Vec<f64>>,
+    /// The probability of each score after five innings, indexed like `score_probabilities`.
+    pub first_five_score_probabilities: Vec<Vec<f64>>,
+    pub home_win_probability: f64,
+    pub expected_home_runs: f64,
+    pub expected_away_runs: f64,
+    pub total_runs_quantiles: Vec<Quantile>,
+    pub extra_innings_frequency: f64,
+}
+
+/// Simulates a game plate appearance by plate appearance, with each lineup's outcome probabilities taken from the
+/// log5 of its batting rates and the opposing pitching staff's rates.
+pub struct GameSimulator {
+    home_batting: EventRates,
+    away_batting: EventRates,
+    scheduled_innings: u8,
+    extra_inning_runner: bool,
+    /// The possible results of every event in every base-out state, indexed by `[state index][event]`.
+    transitions: Vec<Vec<Vec<Transition>>>,
+}
+
+struct SimulatedGame {
+    home_runs: usize,
+    away_runs: usize,
+    first_five: (usize, usize),
+    extra_innings: bool,
+}
+
+impl GameSimulator {
+    pub fn new(home_batting: EventRates, away_batting: EventRates, scheduled_innings: u8, extra_inning_runner: bool) -> Self {
+        let transitions = (0..24)
+            .map(|index| {
+                let state = BaseOutState {
+                    bases: (index % 8) as u8,
+                    outs: (index / 8) as u8,
+                };
+                EVENTS.iter().map(|event| transitions(state, *event)).collect()
+            })
+            .collect();
+
+        GameSimulator {
+            home_batting,
+            away_batting,
+            scheduled_innings,
+            extra_inning_runner,
+            transitions,
+        }
+    }
+
+    /// Set up a simulation of a stored game from its two team snapshots, relative to the given league rates.
+    pub fn for_game(game: &Game, league: &EventRates) -> Self {
+        GameSimulator::new(
+            EventRates::for_game(&game.home_team.batting_stats, &game.away_team.pitching_stats, league),
+            EventRates::for_game(&game.away_team.batting_stats, &game.home_team.pitching_stats, league),
+            game.setting.scheduled_innings.unwrap_or(DEFAULT_SCHEDULED_INNINGS),
+            game.setting.date.year >= FIRST_EXTRA_INNING_RUNNER_SEASON,
+        )
+    }
+
+    /// Simulate the game `simulations` times. The result depends only on the simulator and `seed`.
+    pub fn simulate(&self, simulations: u32, seed: u64) -> SimulationResult {
+        let mut rng = Rng::new(seed);
+
+        let mut score_counts = vec![vec![0u32; MAX_RUNS + 1]; MAX_RUNS + 1];
+        let mut first_five_counts = vec![vec![0u32; MAX_RUNS + 1]; MAX_RUNS + 1];
+        let mut home_wins = 0.0;
+        let mut extra_innings = 0;
+        for _ in 0..simulations {
+            let game = self.simulate_game(&mut rng);
+
+            score_counts[game.home_runs.min(MAX_RUNS)][game.away_runs.min(MAX_RUNS)] += 1;
+            first_five_counts[game.first_five.0.min(MAX_RUNS)][game.first_five.1.min(MAX_RUNS)] += 1;
+            if game.home_runs > game.away_runs {
+                home_wins += 1.0;
+            } else if game.home_runs == game.away_runs {
+                // abandoned after MAX_INNINGS
+                home_wins += 0.5;
+            }
+            extra_innings += game.extra_innings as u32;
+        }
+
+        let to_probabilities = |counts: Vec<Vec<u32>>| -> Vec<Vec<f64>> {
+            counts
+                .into_iter()
+                .map(|row| row.into_iter().map(|count| count as f64 / simulations as f64).collect())
+                .collect()
+        };
+        let score_probabilities = to_probabilities(score_counts);
+
+        let mut expected_home_runs = 0.0;
+        let mut expected_away_runs = 0.0;
+        let mut total_runs = vec![0.0; 2 * MAX_RUNS + 1];
+        for (home_runs, row) in score_probabilities.iter().enumerate() {
+            for (away_runs, probability) in row.iter().enumerate() {
+                expected_home_runs += home_runs as f64 * probability;
+                expected_away_runs += away_runs as f64 * probability;
+                total_runs[home_runs + away_runs] += probability;
+            }
+        }
+
+        let total_runs_quantiles = TOTAL_RUNS_QUANTILES
+            .iter()
+            .map(|&quantile| {
+                let mut cumulative = 0.0;
+                let runs = total_runs
+                    .iter()
+                    .position(|probability| {
+                        cumulative += probability;
+                        cumulative >= quantile
+                    })
+                    .unwrap_or(total_runs.len() - 1);
+                Quantile { quantile, runs: runs as u8 }
+            })
+            .collect();
+
+        SimulationResult {
+            simulations,
+            score_probabilities,
+            first_five_score_probabilities: to_probabilities(first_five_counts),
+            home_win_probability: home_wins / simulations as f64,
+            expected_home_runs,
+            expected_away_runs,
+            total_runs_quantiles,
+            extra_innings_frequency: extra_innings as f64 / simulations as f64,
+        }
+    }
+
+    fn simulate_game(&self, rng: &mut Rng) -> SimulatedGame {
+        let mut home_runs = 0;
+        let mut away_runs = 0;
+        let mut first_five = (0, 0);
+
+        let mut inning = 1;
+        loop {
+            let extra_inning = inning > self.scheduled_innings;
+            let start = BaseOutState {
+                bases: if extra_inning && self.extra_inning_runner { 2 } else { 0 },
+                outs: 0,
+            };
+
+            away_runs += self.simulate_half_inning(&self.away_batting, start, None, rng);
+
+            // the home team doesn't bat in the last inning if it's already ahead
+            let last_inning = inning >= self.scheduled_innings;
+            if !(last_inning && home_runs > away_runs) {
+                let runs_to_win = last_inning.then(|| away_runs + 1 - home_runs);
+                home_runs += self.simulate_half_inning(&self.home_batting, start, runs_to_win, rng);
+            }
+
+            if inning == 5 {
+                first_five = (home_runs, away_runs);
+            }
+            if (last_inning && home_runs != away_runs) || inning >= MAX_INNINGS {
+                break;
+            }
+            inning += 1;
+        }
+
+        SimulatedGame {
+            home_runs,
+            away_runs,
+            first_five,
+            extra_innings: inning > self.scheduled_innings,
+        }
+    }
+
+    /// Simulate a half-inning from `start`, returning the runs scored. If `runs_to_win` is set, the half-inning ends
+    /// as soon as that many runs score (a walk-off).
+    fn simulate_half_inning(&self, rates: &EventRates, start: BaseOutState, runs_to_win: Option<usize>, rng: &mut Rng) -> usize {
+        let mut state = start;
+        let mut runs = 0;
+        while let Some(index) = state.index() {
+            let mut draw = rng.next_f64();
+            let event = EVENTS
+                .iter()
+                .position(|event| {
+                    draw -= rates.probability(*event);
+                    draw < 0.0
+                })
+                .unwrap_or(EVENTS.len() - 1);
+
+            let results = &self.transitions[index][event];
+            let mut draw = rng.next_f64();
+            let transition = results
+                .iter()
+                .find(|transition| {
+                    draw -= transition.probability;
+                    draw < 0.0
+                })
+                .unwrap_or(&results[results.len() - 1]);
+
+            runs += transition.runs as usize;
+            state = transition.state;
+            if runs_to_win.is_some_and(|runs_to_win| runs >= runs_to_win) {
+                break;
+            }
+        }
+
+        runs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(grid: &[Vec<f64>]) -> f64 {
+        grid.iter().flatten().sum()
+    }
+
+    #[test]
+    fn score_distributions_sum_to_one() {
+        let league = EventRates::LEAGUE_AVERAGE;
+        let result = GameSimulator::new(league, league, DEFAULT_SCHEDULED_INNINGS, true).simulate(2000, 0);
+
+        assert!((total(&result.score_probabilities) - 1.0).abs() < 1e-9);
+        assert!((total(&result.first_five_score_probabilities) - 1.0).abs() < 1e-9);
+        assert!(result.home_win_probability > 0.0 && result.home_win_probability < 1.0);
+    }
+
+    #[test]
+    fn simulation_depends_only_on_seed() {
+        let league = EventRates::LEAGUE_AVERAGE;
+        let simulator = GameSimulator::new(league, league, DEFAULT_SCHEDULED_INNINGS, false);
+
+        assert_eq!(simulator.simulate(500, 7).score_probabilities, simulator.simulate(500, 7).score_probabilities);
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{fetch_json, Date, HittingStats, PitchingStats};
+
+/// A team's situational stats over the 30 days before a game.
+///
+/// Each split is `None` if the team had no games in it over the window (e.g. no home games during a road trip).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Splits {
+    pub home_hitting: Option<HittingStats>,
+    pub road_hitting: Option<HittingStats>,
+    pub home_pitching: Option<PitchingStats>,
+    pub road_pitching: Option<PitchingStats>,
+    /// Hitting against left-handed pitchers.
+    pub vs_left_hitting: Option<HittingStats>,
+    /// Hitting against right-handed pitchers.
+    pub vs_right_hitting: Option<HittingStats>,
+}
+
+impl Splits {
+    /// Get the team's split stats over the same window as [`crate::model::Team::by_date`].
+    pub async fn by_date(team_id: u8, end_date: &Date) -> Result<Self, String> {
+        let url = format!(
+            "https://statsapi.mlb.com/api/v1/teams/{}/stats?season={}&stats=statSplits&group=hitting,pitching&sitCodes=h,a,vl,vr&startDate={}&endDate={}",
+            team_id,
+            end_date.year,
+            end_date.minus_n_days(30),
+            end_date,
+        );
+        let stats = fetch_json(&url).await?;
+        let Some(groups) = stats["stats"].as_array() else {
+            return Err(format!("Failed to parse split stats: {url}"));
+        };
+
+        let split = |group: &str, code: &str| groups
+            .iter()
+            .filter(|stats| stats["group"]["displayName"].as_str() == Some(group))
+            .flat_map(|stats| stats["splits"].as_array().into_iter().flatten())
+            .find(|split| split["split"]["code"].as_str() == Some(code))
+            .map(|split| &split["stat"]);
+
+        Ok(Splits {
+            home_hitting: split("hitting", "h").map(HittingStats::from_mlb),
+            road_hitting: split("hitting", "a").map(HittingStats::from_mlb),
+            home_pitching: split("pitching", "h").map(PitchingStats::from_mlb),
+            road_pitching: split("pitching", "a").map(PitchingStats::from_mlb),
+            vs_left_hitting: split("hitting", "vl").map(HittingStats::from_mlb),
+            vs_right_hitting: split("hitting", "vr").map(HittingStats::from_mlb),
+        })
+    }
+}
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{fetch_json, Date};
+
+/// Parse a games back value (e.g. `"2.5"`), where `"-"` is the leader. Teams ahead of a wild card spot (e.g. `"+1.0"`)
+/// are negative.
+fn parse_games_back(games_back: &serde_json::Value) -> Option<f32> {
+    match games_back.as_str()? {
+        "-" => Some(0.0),
+        games_back => match games_back.strip_prefix('+') {
+            Some(games_ahead) => games_ahead.parse::<f32>().ok().map(|games_ahead| -games_ahead),
+            None => games_back.parse().ok(),
+        },
+    }
+}
+
+/// A team's place in the standings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Standing {
+    pub team_id: u8,
+    pub division_id: u16,
+    pub wins: u16,
+    pub losses: u16,
+    pub win_percentage: Option<f32>,
+    /// Games behind the division leader.
+    pub games_back: Option<f32>,
+    /// Games behind the last wild card spot.
+    pub wild_card_games_back: Option<f32>,
+    pub division_rank: Option<u8>,
+    pub run_differential: i16,
+    /// The length of the team's current streak: positive for wins, negative for losses.
+    pub streak: i8,
+    pub last_10_wins: u8,
+    pub last_10_losses: u8,
+}
+
+impl Standing {
+    fn from_mlb(division_id: u16, record: &serde_json::Value) -> Option<Self> {
+        let streak = record["streak"]["streakNumber"].as_i64().unwrap_or(0) as i8;
+        let last_10 = record["records"]["splitRecords"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|split| split["type"].as_str() == Some("lastTen"));
+
+        Some(Standing {
+            team_id: record["team"]["id"].as_u64()? as u8,
+            division_id,
+            wins: record["wins"].as_u64().unwrap_or(0) as u16,
+            losses: record["losses"].as_u64().unwrap_or(0) as u16,
+            win_percentage: record["winningPercentage"].as_str().and_then(|pct| pct.parse().ok()),
+            games_back: parse_games_back(&record["gamesBack"]),
+            wild_card_games_back: parse_games_back(&record["wildCardGamesBack"]),
+            division_rank: record["divisionRank"].as_str().and_then(|rank| rank.parse().ok()),
+            run_differential: record["runDifferential"].as_i64().unwrap_or(0) as i16,
+            streak: if record["streak"]["streakType"].as_str() == Some("losses") { -streak } else { streak },
+            last_10_wins: last_10.and_then(|split| split["wins"].as_u64()).unwrap_or(0) as u8,
+            last_10_losses: last_10.and_then(|split| split["losses"].as_u64()).unwrap_or(0) as u8,
+        })
+    }
+}
+
+/// The standings of every team at the end of a day, stored in `data/standings/<date>.json`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Standings {
+    pub date: Date,
+    pub teams: Vec<Standing>,
+}
+
+impl Standings {
+    /// Get a team's standing on the morning of `date`, i.e. after the previous day's games.
+    pub async fn by_date(team_id: u8, date: &Date) -> Result<Option<Standing>, String> {
+        let standings = Standings::get(&date.previous_day()).await?;
+
+        Ok(standings.teams.into_iter().find(|standing| standing.team_id == team_id))
+    }
+
+    /// Load the standings at the end of `date` from disk, fetching and saving them first if they haven't been stored yet.
+    pub async fn get(date: &Date) -> Result<Self, String> {
+        if let Some(standings) = Standings::load(date) {
+            return Ok(standings);
+        }
+
+        let standings = Standings::fetch(date).await?;
+        standings.save()?;
+
+        Ok(standings)
+    }
+
+    async fn fetch(date: &Date) -> Result<Self, String> {
+        let url = format!(
+            "https://statsapi.mlb.com/api/v1/standings?leagueId=103,104&season={}&date={}&standingsTypes=regularSeason",
+            date.year,
+            date,
+        );
+        let standings = fetch_json(&url).await?;
+        let Some(records) = standings["records"].as_array() else {
+            return Err(format!("Failed to parse standings: {url}"));
+        };
+
+        let teams = records
+            .iter()
+            .flat_map(|division| {
+                let division_id = division["division"]["id"].as_u64().unwrap_or(0) as u16;
+                division["teamRecords"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(move |record| Standing::from_mlb(division_id, record))
+            })
+            .collect();
+
+        Ok(Standings { date: *date, teams })
+    }
+
+    fn file_path(date: &Date) -> String {
+        format!("data/standings/{}.json", date)
+    }
+
+    fn load(date: &Date) -> Option<Self> {
+        let json = std::fs::read_to_string(Standings::file_path(date)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        std::fs::create_dir_all("data/standings")
+            .map_err(|e| format!("Failed to create directories: {}", e))?;
+
+        let file_path = Standings::file_path(&self.date);
+        let json = serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize standings: {}", e))?;
+        std::fs::write(&file_path, json)
+            .map_err(|e| format!("Failed to write file {}: {}", file_path, e))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use crate::linescore::DEFAULT_SCHEDULED_INNINGS;
+use crate::model::{Date, Game};
+use crate::ratings::Ratings;
+
+/// The league's runs per team per game before any games have been seen.
+const INITIAL_RUNS_PER_GAME: f64 = 4.5;
+
+/// The weight of each game in the running league averages of home and away runs.
+const LEAGUE_AVERAGE_WEIGHT: f64 = 0.001;
+
+/// The variance of a team's offense or defense strength (in runs per game) before any of its games have been seen.
+const INITIAL_VARIANCE: f64 = 0.25;
+
+/// How much the variance of each strength grows per day, as teams change over a season.
+const DAILY_VARIANCE: f64 = 0.0005;
+
+/// The share of each strength carried over into the next season; the rest of it is replaced by the initial
+/// uncertainty.
+const SEASON_CARRYOVER: f64 = 0.6;
+
+/// The variance of a team's runs in a game around its expected runs.
+const OBSERVATION_VARIANCE: f64 = 9.5;
+
+/// Tracks every team's offense (runs scored) and defense (runs allowed) strength relative to the league average, with
+/// a Kalman filter over all of them jointly.
+///
+/// Each team's runs in a game are observed as the league average plus its offense plus the opponent's defense, with
+/// noise, and the strengths drift day by day as a random walk.
+#[derive(Debug, Clone)]
+pub struct StrengthFilter {
+    /// Each team's offense is at `2 * index` and its defense at `2 * index + 1`.
+    indices: HashMap<u8, usize>,
+    strengths: Vec<f64>,
+    /// The covariance of the strengths, row-major.
+    covariance: Vec<f64>,
+    home_runs_per_game: f64,
+    away_runs_per_game: f64,
+    date: Option<Date>,
+}
+
+/// A team's expected runs in a game, and the standard deviation of that expectation.
+#[derive(Debug, Clone, Copy)]
+pub struct ExpectedRuns {
+    pub runs: f64,
+    pub uncertainty: f64,
+}
+
+fn exposure(game: &Game) -> f64 {
+    game.setting.scheduled_innings.unwrap_or(DEFAULT_SCHEDULED_INNINGS) as f64 / 9.0
+}
+
+impl StrengthFilter {
+    pub fn new(team_ids: impl IntoIterator<Item = u8>) -> Self {
+        let mut indices = HashMap::new();
+        for team_id in team_ids {
+            let index = indices.len();
+            indices.entry(team_id).or_insert(index);
+        }
+
+        let size = 2 * indices.len();
+        let mut covariance = vec![0.0; size * size];
+        for i in 0..size {
+            covariance[i * size + i] = INITIAL_VARIANCE;
+        }
+
+        StrengthFilter {
+            indices,
+            strengths: vec![0.0; size],
+            covariance,
+            home_runs_per_game: INITIAL_RUNS_PER_GAME,
+            away_runs_per_game: INITIAL_RUNS_PER_GAME,
+            date: None,
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.strengths.len()
+    }
+
+    fn offense_index(&self, team_id: u8) -> usize {
+        2 * self.indices[&team_id]
+    }
+
+    fn defense_index(&self, team_id: u8) -> usize {
+        2 * self.indices[&team_id] + 1
+    }
+
+    /// The team's offense and defense, in runs per game above average (so a good defense is negative).
+    pub fn strengths(&self, team_id: u8) -> (f64, f64) {
+        (self.strengths[self.offense_index(team_id)], self.strengths[self.defense_index(team_id)])
+    }
+
+    /// Advance the filter to the day of the game: let the strengths drift for the days since the last game, and
+    /// regress them toward average at the start of a new season.
+    pub fn start_game(&mut self, game: &Game) {
+        let date = game.setting.date;
+        let size = self.size();
+        match self.date {
+            Some(previous) if previous.year != date.year => {
+                let carryover_variance = SEASON_CARRYOVER * SEASON_CARRYOVER;
+                for strength in self.strengths.iter_mut() {
+                    *strength *= SEASON_CARRYOVER;
+                }
+                for value in self.covariance.iter_mut() {
+                    *value *= carryover_variance;
+                }
+                for i in 0..size {
+                    self.covariance[i * size + i] += (1.0 - carryover_variance) * INITIAL_VARIANCE;
+                }
+            },
+            Some(previous) if previous < date => {
+                let days = date.days_since(&previous) as f64;
+                for i in 0..size {
+                    self.covariance[i * size + i] += DAILY_VARIANCE * days;
+                }
+            },
+            _ => {},
+        }
+        self.date = Some(date);
+    }
+
+    /// The expected runs of a team batting against another, and its uncertainty from the uncertainty of the
+    /// strengths.
+    fn expected(&self, offense: usize, defense: usize, league_runs: f64, exposure: f64) -> ExpectedRuns {
+        let size = self.size();
+        let variance = self.covariance[offense * size + offense]
+            + self.covariance[defense * size + defense]
+            + 2.0 * self.covariance[offense * size + defense];
+
+        ExpectedRuns {
+            runs: exposure * (league_runs + self.strengths[offense] + self.strengths[defense]),
+            uncertainty: exposure * variance.max(0.0).sqrt(),
+        }
+    }
+
+    /// The home and away teams' expected runs in a game.
+    pub fn expected_runs(&self, game: &Game) -> (ExpectedRuns, ExpectedRuns) {
+        let home = game.home_team.team_id;
+        let away = game.away_team.team_id;
+        let exposure = exposure(game);
+
+        (
+            self.expected(self.offense_index(home), self.defense_index(away), self.home_runs_per_game, exposure),
+            self.expected(self.offense_index(away), self.defense_index(home), self.away_runs_per_game, exposure),
+        )
+    }
+
+    /// Update the strengths from one team's runs.
+    fn observe(&mut self, offense: usize, defense: usize, league_runs: f64, exposure: f64, runs: f64) {
+        let size = self.size();
+        let predicted = self.expected(offense, defense, league_runs, exposure);
+
+        // the covariance of every strength with the observation
+        let gains: Vec<f64> = (0..size)
+            .map(|i| exposure * (self.covariance[i * size + offense] + self.covariance[i * size + defense]))
+            .collect();
+        let innovation_variance = exposure * (gains[offense] + gains[defense]) + OBSERVATION_VARIANCE;
+
+        let innovation = runs - predicted.runs;
+        for (strength, gain) in self.strengths.iter_mut().zip(&gains) {
+            *strength += gain / innovation_variance * innovation;
+        }
+        for i in 0..size {
+            for j in 0..size {
+                self.covariance[i * size + j] -= gains[i] * gains[j] / innovation_variance;
+            }
+        }
+    }
+
+    /// Update the strengths and league averages from the game's result.
+    pub fn update(&mut self, game: &Game) {
+        self.start_game(game);
+
+        let home = game.home_team.team_id;
+        let away = game.away_team.team_id;
+        let exposure = exposure(game);
+        let home_runs = game.result.home_team_score as f64;
+        let away_runs = game.result.away_team_score as f64;
+
+        self.observe(self.offense_index(home), self.defense_index(away), self.home_runs_per_game, exposure, home_runs);
+        self.observe(self.offense_index(away), self.defense_index(home), self.away_runs_per_game, exposure, away_runs);
+
+        self.home_runs_per_game += LEAGUE_AVERAGE_WEIGHT * (home_runs / exposure - self.home_runs_per_game);
+        self.away_runs_per_game += LEAGUE_AVERAGE_WEIGHT * (away_runs / exposure - self.away_runs_per_game);
+    }
+}
+
+/// Set both teams' offense and defense strengths and expected runs going into every game.
+///
+/// `games` must be in chronological order.
+pub fn assign_strengths(games: &mut [Game]) {
+    let mut filter = StrengthFilter::new(games.iter().flat_map(|game| [game.home_team.team_id, game.away_team.team_id]));
+    for game in games.iter_mut() {
+        filter.start_game(game);
+
+        let (home_expected, away_expected) = filter.expected_runs(game);
+        for (team, expected) in [(&mut game.home_team, home_expected), (&mut game.away_team, away_expected)] {
+            let (offense, defense) = filter.strengths(team.team_id);
+            let ratings = team.ratings.get_or_insert_with(Ratings::default);
+            ratings.offense = Some(offense as f32);
+            ratings.defense = Some(defense as f32);
+            ratings.expected_runs = Some(expected.runs as f32);
+            ratings.expected_runs_uncertainty = Some(expected.uncertainty as f32);
+        }
+
+        filter.update(game);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_update_shrinks_the_variance() {
+        let mut games = vec![
+            Game::with_score("2024-04-01", 1, 2, 9, 2),
+            Game::with_score("2024-04-02", 1, 2, 4, 4),
+        ];
+        assign_strengths(&mut games);
+
+        let before = games[0].home_team.ratings.as_ref().unwrap();
+        let after = games[1].home_team.ratings.as_ref().unwrap();
+        assert_eq!(before.offense, Some(0.0));
+        // by much more than it drifts in the day between the games
+        assert!(after.expected_runs_uncertainty.unwrap() < before.expected_runs_uncertainty.unwrap());
+        // and the strengths moved toward the first game's result, which the second game's ratings come from
+        assert!(after.offense.unwrap() > 0.0);
+        assert!(games[1].away_team.ratings.as_ref().unwrap().defense.unwrap() > 0.0);
+
+        let mut filter = StrengthFilter::new([1, 2]);
+        let size = filter.size();
+        let variances: Vec<f64> = (0..size).map(|i| filter.covariance[i * size + i]).collect();
+        filter.update(&games[0]);
+        for (i, variance) in variances.iter().enumerate() {
+            assert!(filter.covariance[i * size + i] < *variance, "variance {} didn't shrink", i);
+        }
+    }
+}
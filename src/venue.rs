@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::fetch_json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoofType {
+    Open,
+    Retractable,
+    Dome,
+}
+
+impl RoofType {
+    fn from_mlb(roof_type: &str) -> Option<Self> {
+        match roof_type {
+            "Open" => Some(RoofType::Open),
+            "Retractable" => Some(RoofType::Retractable),
+            "Dome" => Some(RoofType::Dome),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Surface {
+    Grass,
+    ArtificialTurf,
+}
+
+impl Surface {
+    fn from_mlb(turf_type: &str) -> Option<Self> {
+        match turf_type {
+            "Grass" => Some(Surface::Grass),
+            "Artificial Turf" => Some(Surface::ArtificialTurf),
+            _ => None,
+        }
+    }
+}
+
+/// A ballpark, stored in `data/venues/<venue id>.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Venue {
+    pub id: u16,
+    pub name: String,
+    pub roof_type: Option<RoofType>,
+    pub surface: Option<Surface>,
+    /// Elevation above sea level, in feet.
+    pub altitude: Option<u16>,
+    pub latitude: Option<f32>,
+    pub longitude: Option<f32>,
+    /// Hours from UTC of the venue's local time, as of when it was fetched.
+    pub utc_offset: Option<i8>,
+}
+
+impl Venue {
+    /// Load the venue from disk, fetching and saving it first if it hasn't been stored yet.
+    pub async fn get(venue_id: u16) -> Result<Self, String> {
+        if let Some(venue) = Venue::load(venue_id) {
+            return Ok(venue);
+        }
+
+        let venue = Venue::fetch(venue_id).await?;
+        venue.save()?;
+
+        Ok(venue)
+    }
+
+    async fn fetch(venue_id: u16) -> Result<Self, String> {
+        let url = format!("https://statsapi.mlb.com/api/v1/venues/{}?hydrate=location,fieldInfo,timezone", venue_id);
+        let venues = fetch_json(&url).await?;
+        let venue = &venues["venues"][0];
+
+        let Some(name) = venue["name"].as_str() else {
+            return Err(format!("Failed to parse venue: {url}"));
+        };
+        let location = &venue["location"];
+
+        Ok(Venue {
+            id: venue_id,
+            name: name.to_string(),
+            roof_type: venue["fieldInfo"]["roofType"].as_str().and_then(RoofType::from_mlb),
+            surface: venue["fieldInfo"]["turfType"].as_str().and_then(Surface::from_mlb),
+            altitude: location["elevation"].as_u64().map(|altitude| altitude as u16),
+            latitude: location["defaultCoordinates"]["latitude"].as_f64().map(|latitude| latitude as f32),
+            longitude: location["defaultCoordinates"]["longitude"].as_f64().map(|longitude| longitude as f32),
+            utc_offset: venue["timeZone"]["offset"].as_i64().map(|offset| offset as i8),
+        })
+    }
+
+    fn file_path(venue_id: u16) -> String {
+        format!("data/venues/{}.json", venue_id)
+    }
+
+    fn load(venue_id: u16) -> Option<Self> {
+        let json = std::fs::read_to_string(Venue::file_path(venue_id)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        std::fs::create_dir_all("data/venues")
+            .map_err(|e| format!("Failed to create directories: {}", e))?;
+
+        let file_path = Venue::file_path(self.id);
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize venue: {}", e))?;
+        std::fs::write(&file_path, json)
+            .map_err(|e| format!("Failed to write file {}: {}", file_path, e))?;
+
+        Ok(())
+    }
+}
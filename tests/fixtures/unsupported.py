@@ -0,0 +1,83 @@
+"""
+Write `unsupported.onnx`, a small network with a `Cast` node, which `tests/parity.rs` checks the rust interpreter rejects
+when loading. tf2onnx doesn't emit such a node for the networks `training/nn.py` trains, so the protobuf is written by
+hand. The models whose predictions are checked are written by `training/parity_fixtures.py`. Run this from anywhere; the
+files are written next to it.
+"""
+
+import json
+import os
+import struct
+
+
+FIXTURES_DIR = os.path.dirname(os.path.abspath(__file__))
+
+FEATURE_NAMES = ["a", "b", "c"]
+HIDDEN = 4
+
+
+# onnx: the protobuf encoding of a graph of MatMul, Add, Relu and Dropout nodes
+
+def varint(value: int) -> bytes:
+    encoded = b""
+    while True:
+        byte = value & 0x7F
+        value >>= 7
+        if value:
+            encoded += bytes([byte | 0x80])
+        else:
+            return encoded + bytes([byte])
+
+
+def key(number: int, wire_type: int) -> bytes:
+    return varint(number << 3 | wire_type)
+
+
+def length_delimited(number: int, data: bytes) -> bytes:
+    return key(number, 2) + varint(len(data)) + data
+
+
+def string(number: int, value: str) -> bytes:
+    return length_delimited(number, value.encode())
+
+
+def tensor(name: str, dims, values, raw: bool) -> bytes:
+    encoded = b"".join(key(1, 0) + varint(dim) for dim in dims) + key(2, 0) + varint(1) + string(8, name)
+    data = struct.pack(f"<{len(values)}f", *values)
+    # exporters write either raw_data or packed float_data
+    return encoded + (length_delimited(9, data) if raw else length_delimited(4, data))
+
+
+def node(op_type: str, inputs, outputs) -> bytes:
+    return (
+        b"".join(string(1, name) for name in inputs)
+        + b"".join(string(2, name) for name in outputs)
+        + string(4, op_type)
+    )
+
+
+def model(nodes, initializers) -> bytes:
+    graph = b"".join(length_delimited(1, encoded) for encoded in nodes) + string(2, "graph")
+    graph += b"".join(length_delimited(5, encoded) for encoded in initializers)
+    graph += length_delimited(11, string(1, "features")) + length_delimited(12, string(1, "output"))
+    return key(1, 0) + varint(8) + string(2, "tf2onnx") + length_delimited(7, graph)
+
+
+weights_1 = [0.1 * (i - j) for i in range(len(FEATURE_NAMES)) for j in range(HIDDEN)]
+weights_2 = [0.1 * (j + k) for j in range(HIDDEN) for k in range(2)]
+initializers = [
+    tensor("W1", [len(FEATURE_NAMES), HIDDEN], weights_1, True),
+    tensor("W2", [HIDDEN, 2], weights_2, True),
+    tensor("b2", [2], [4.0, 4.5], False),
+]
+nodes = [
+    node("MatMul", ["features", "W1"], ["hidden"]),
+    node("Relu", ["hidden"], ["activated"]),
+    node("MatMul", ["activated", "W2"], ["output_unbiased"]),
+    node("Cast", ["output_unbiased"], ["cast"]),
+    node("Add", ["cast", "b2"], ["output"]),
+]
+with open(f"{FIXTURES_DIR}/unsupported.onnx", "wb") as f:
+    f.write(model(nodes, initializers))
+with open(f"{FIXTURES_DIR}/unsupported_scaler.json", "w") as f:
+    json.dump({"feature_names": FEATURE_NAMES, "means": [0.0] * len(FEATURE_NAMES), "scales": [1.0] * len(FEATURE_NAMES)}, f)
@@ -0,0 +1,38 @@
+//! Checks that models trained and exported by the Python training code predict the same in rust as they do in Python
+//! (xgboost's `Booster.predict` and onnxruntime), on the fixtures written by `training/parity_fixtures.py`.
+
+use baseball::models::onnx::OnnxModel;
+use baseball::models::parity::{self, Fixture};
+use baseball::models::xgboost::XGBoostModel;
+use baseball::models::Regressor;
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn assert_parity(model: &dyn Regressor, name: &str) {
+    let fixture = Fixture::load(&fixture_path(&format!("{}_fixture.json", name))).unwrap();
+    let difference = parity::max_difference(model, &fixture).unwrap();
+    assert!(difference <= parity::TOLERANCE, "{} predictions differ from python by {:e}", name, difference);
+}
+
+#[test]
+#[ignore = "needs the fixtures written by `python training/parity_fixtures.py`"]
+fn xgboost_matches_python() {
+    let model = XGBoostModel::load(&fixture_path("xgboost.json"), &fixture_path("xgboost_scaler.json")).unwrap();
+    assert_parity(&model, "xgboost");
+}
+
+#[test]
+#[ignore = "needs the fixtures written by `python training/parity_fixtures.py`"]
+fn onnx_matches_python() {
+    let model = OnnxModel::load(&fixture_path("nn.onnx"), &fixture_path("nn_scaler.json")).unwrap();
+    assert_parity(&model, "nn");
+}
+
+#[test]
+fn onnx_rejects_unsupported_operators_when_loading() {
+    // written by `tests/fixtures/unsupported.py`
+    let error = OnnxModel::load(&fixture_path("unsupported.onnx"), &fixture_path("unsupported_scaler.json")).unwrap_err();
+    assert!(error.contains("Unsupported ONNX operator Cast"), "{}", error);
+}
@@ -5,26 +5,74 @@ import json
 import os
 
 
-FEATURES = ['setting.date.year', 'setting.date.month', 'setting.date.day', 'home_team.team_id', 'home_team.batting_stats.games_played', 'home_team.batting_stats.ground_outs', 'home_team.batting_stats.air_outs', 'home_team.batting_stats.runs', 'home_team.batting_stats.doubles', 'home_team.batting_stats.triples', 'home_team.batting_stats.home_runs', 'home_team.batting_stats.strike_outs', 'home_team.batting_stats.base_on_balls', 'home_team.batting_stats.intentional_walks', 'home_team.batting_stats.hits', 'home_team.batting_stats.hit_by_pitch', 'home_team.batting_stats.avg', 'home_team.batting_stats.at_bats', 'home_team.batting_stats.obp', 'home_team.batting_stats.slg', 'home_team.batting_stats.ops', 'home_team.batting_stats.caught_stealing', 'home_team.batting_stats.stolen_bases', 'home_team.batting_stats.stolen_base_percentage', 'home_team.batting_stats.ground_into_double_play', 'home_team.batting_stats.number_of_pitches', 'home_team.batting_stats.plate_appearances', 'home_team.batting_stats.total_bases', 'home_team.batting_stats.rbi', 'home_team.batting_stats.left_on_base', 'home_team.batting_stats.sac_bunts', 'home_team.batting_stats.sac_flies', 'home_team.batting_stats.babip', 'home_team.batting_stats.ground_outs_to_airouts', 'home_team.batting_stats.at_bats_per_home_run', 'home_team.pitching_stats.games_played', 'home_team.pitching_stats.games_started', 'home_team.pitching_stats.ground_outs', 'home_team.pitching_stats.air_outs', 'home_team.pitching_stats.runs', 'home_team.pitching_stats.doubles', 'home_team.pitching_stats.triples', 'home_team.pitching_stats.home_runs', 'home_team.pitching_stats.strike_outs', 'home_team.pitching_stats.base_on_balls', 'home_team.pitching_stats.intentional_walks', 'home_team.pitching_stats.hits', 'home_team.pitching_stats.hit_by_pitch', 'home_team.pitching_stats.avg', 'home_team.pitching_stats.at_bats', 'home_team.pitching_stats.era', 'home_team.pitching_stats.innings_pitched', 'home_team.pitching_stats.wins', 'home_team.pitching_stats.losses', 'home_team.pitching_stats.ties', 'home_team.pitching_stats.saves', 'home_team.pitching_stats.save_opportunities', 'home_team.pitching_stats.holds', 'home_team.pitching_stats.blown_saves', 'home_team.pitching_stats.earned_runs', 'home_team.pitching_stats.whip', 'home_team.pitching_stats.batters_faced', 'home_team.pitching_stats.outs', 'home_team.pitching_stats.games_pitched', 'home_team.pitching_stats.complete_games', 'home_team.pitching_stats.shutouts', 'home_team.pitching_stats.strikes', 'home_team.pitching_stats.strike_percentage', 'home_team.pitching_stats.hit_batsmen', 'home_team.pitching_stats.balks', 'home_team.pitching_stats.wild_pitches', 'home_team.pitching_stats.pickoffs', 'home_team.pitching_stats.ground_outs_to_airouts', 'home_team.pitching_stats.win_percentage', 'home_team.pitching_stats.pitches_per_inning', 'home_team.pitching_stats.games_finished', 'home_team.pitching_stats.strikeout_walk_ratio', 'home_team.pitching_stats.strikeouts_per_9inn', 'home_team.pitching_stats.walks_per_9inn', 'home_team.pitching_stats.hits_per_9inn', 'home_team.pitching_stats.runs_scored_per_9', 'home_team.pitching_stats.home_runs_per_9', 'home_team.pitching_stats.sac_bunts', 'home_team.pitching_stats.sac_flies', 'home_team.fielding_stats.games_played', 'home_team.fielding_stats.games_started', 'home_team.fielding_stats.assists', 'home_team.fielding_stats.put_outs', 'home_team.fielding_stats.errors', 'home_team.fielding_stats.chances', 'home_team.fielding_stats.fielding', 'home_team.fielding_stats.range_factor_per_game', 'home_team.fielding_stats.range_factor_per_9inn', 'home_team.fielding_stats.innings', 'home_team.fielding_stats.games', 'home_team.fielding_stats.double_plays', 'home_team.fielding_stats.triple_plays', 'home_team.fielding_stats.throwing_errors', 'away_team.team_id', 'away_team.batting_stats.games_played', 'away_team.batting_stats.ground_outs', 'away_team.batting_stats.air_outs', 'away_team.batting_stats.runs', 'away_team.batting_stats.doubles', 'away_team.batting_stats.triples', 'away_team.batting_stats.home_runs', 'away_team.batting_stats.strike_outs', 'away_team.batting_stats.base_on_balls', 'away_team.batting_stats.intentional_walks', 'away_team.batting_stats.hits', 'away_team.batting_stats.hit_by_pitch', 'away_team.batting_stats.avg', 'away_team.batting_stats.at_bats', 'away_team.batting_stats.obp', 'away_team.batting_stats.slg', 'away_team.batting_stats.ops', 'away_team.batting_stats.caught_stealing', 'away_team.batting_stats.stolen_bases', 'away_team.batting_stats.stolen_base_percentage', 'away_team.batting_stats.ground_into_double_play', 'away_team.batting_stats.number_of_pitches', 'away_team.batting_stats.plate_appearances', 'away_team.batting_stats.total_bases', 'away_team.batting_stats.rbi', 'away_team.batting_stats.left_on_base', 'away_team.batting_stats.sac_bunts', 'away_team.batting_stats.sac_flies', 'away_team.batting_stats.babip', 'away_team.batting_stats.ground_outs_to_airouts', 'away_team.batting_stats.at_bats_per_home_run', 'away_team.pitching_stats.games_played', 'away_team.pitching_stats.games_started', 'away_team.pitching_stats.ground_outs', 'away_team.pitching_stats.air_outs', 'away_team.pitching_stats.runs', 'away_team.pitching_stats.doubles', 'away_team.pitching_stats.triples', 'away_team.pitching_stats.home_runs', 'away_team.pitching_stats.strike_outs', 'away_team.pitching_stats.base_on_balls', 'away_team.pitching_stats.intentional_walks', 'away_team.pitching_stats.hits', 'away_team.pitching_stats.hit_by_pitch', 'away_team.pitching_stats.avg', 'away_team.pitching_stats.at_bats', 'away_team.pitching_stats.era', 'away_team.pitching_stats.innings_pitched', 'away_team.pitching_stats.wins', 'away_team.pitching_stats.losses', 'away_team.pitching_stats.ties', 'away_team.pitching_stats.saves', 'away_team.pitching_stats.save_opportunities', 'away_team.pitching_stats.holds', 'away_team.pitching_stats.blown_saves', 'away_team.pitching_stats.earned_runs', 'away_team.pitching_stats.whip', 'away_team.pitching_stats.batters_faced', 'away_team.pitching_stats.outs', 'away_team.pitching_stats.games_pitched', 'away_team.pitching_stats.complete_games', 'away_team.pitching_stats.shutouts', 'away_team.pitching_stats.strikes', 'away_team.pitching_stats.strike_percentage', 'away_team.pitching_stats.hit_batsmen', 'away_team.pitching_stats.balks', 'away_team.pitching_stats.wild_pitches', 'away_team.pitching_stats.pickoffs', 'away_team.pitching_stats.ground_outs_to_airouts', 'away_team.pitching_stats.win_percentage', 'away_team.pitching_stats.pitches_per_inning', 'away_team.pitching_stats.games_finished', 'away_team.pitching_stats.strikeout_walk_ratio', 'away_team.pitching_stats.strikeouts_per_9inn', 'away_team.pitching_stats.walks_per_9inn', 'away_team.pitching_stats.hits_per_9inn', 'away_team.pitching_stats.runs_scored_per_9', 'away_team.pitching_stats.home_runs_per_9', 'away_team.pitching_stats.sac_bunts', 'away_team.pitching_stats.sac_flies', 'away_team.fielding_stats.games_played', 'away_team.fielding_stats.games_started', 'away_team.fielding_stats.assists', 'away_team.fielding_stats.put_outs', 'away_team.fielding_stats.errors', 'away_team.fielding_stats.chances', 'away_team.fielding_stats.fielding', 'away_team.fielding_stats.range_factor_per_game', 'away_team.fielding_stats.range_factor_per_9inn', 'away_team.fielding_stats.innings', 'away_team.fielding_stats.games', 'away_team.fielding_stats.double_plays', 'away_team.fielding_stats.triple_plays', 'away_team.fielding_stats.throwing_errors', 'result.home_team_score', 'result.away_team_score']
+FEATURES = ['setting.date.year', 'setting.date.month', 'setting.date.day', 'setting.venue.id', 'setting.venue.roof_type', 'setting.venue.surface', 'setting.venue.altitude', 'setting.park_factor', 'setting.day_night', 'setting.start_time.hour', 'setting.start_time.minute', 'setting.weather.condition', 'setting.weather.temperature', 'setting.weather.wind_speed', 'setting.weather.wind_direction', 'setting.scheduled_innings', 'setting.double_header', 'setting.game_number', 'home_team.team_id', 'home_team.batting_stats.games_played', 'home_team.batting_stats.ground_outs', 'home_team.batting_stats.air_outs', 'home_team.batting_stats.runs', 'home_team.batting_stats.doubles', 'home_team.batting_stats.triples', 'home_team.batting_stats.home_runs', 'home_team.batting_stats.strike_outs', 'home_team.batting_stats.base_on_balls', 'home_team.batting_stats.intentional_walks', 'home_team.batting_stats.hits', 'home_team.batting_stats.hit_by_pitch', 'home_team.batting_stats.avg', 'home_team.batting_stats.at_bats', 'home_team.batting_stats.obp', 'home_team.batting_stats.slg', 'home_team.batting_stats.ops', 'home_team.batting_stats.caught_stealing', 'home_team.batting_stats.stolen_bases', 'home_team.batting_stats.stolen_base_percentage', 'home_team.batting_stats.ground_into_double_play', 'home_team.batting_stats.number_of_pitches', 'home_team.batting_stats.plate_appearances', 'home_team.batting_stats.total_bases', 'home_team.batting_stats.rbi', 'home_team.batting_stats.left_on_base', 'home_team.batting_stats.sac_bunts', 'home_team.batting_stats.sac_flies', 'home_team.batting_stats.babip', 'home_team.batting_stats.ground_outs_to_airouts', 'home_team.batting_stats.at_bats_per_home_run', 'home_team.pitching_stats.games_played', 'home_team.pitching_stats.games_started', 'home_team.pitching_stats.ground_outs', 'home_team.pitching_stats.air_outs', 'home_team.pitching_stats.runs', 'home_team.pitching_stats.doubles', 'home_team.pitching_stats.triples', 'home_team.pitching_stats.home_runs', 'home_team.pitching_stats.strike_outs', 'home_team.pitching_stats.base_on_balls', 'home_team.pitching_stats.intentional_walks', 'home_team.pitching_stats.hits', 'home_team.pitching_stats.hit_by_pitch', 'home_team.pitching_stats.avg', 'home_team.pitching_stats.at_bats', 'home_team.pitching_stats.era', 'home_team.pitching_stats.innings_pitched', 'home_team.pitching_stats.wins', 'home_team.pitching_stats.losses', 'home_team.pitching_stats.ties', 'home_team.pitching_stats.saves', 'home_team.pitching_stats.save_opportunities', 'home_team.pitching_stats.holds', 'home_team.pitching_stats.blown_saves', 'home_team.pitching_stats.earned_runs', 'home_team.pitching_stats.whip', 'home_team.pitching_stats.batters_faced', 'home_team.pitching_stats.outs', 'home_team.pitching_stats.games_pitched', 'home_team.pitching_stats.complete_games', 'home_team.pitching_stats.shutouts', 'home_team.pitching_stats.strikes', 'home_team.pitching_stats.strike_percentage', 'home_team.pitching_stats.hit_batsmen', 'home_team.pitching_stats.balks', 'home_team.pitching_stats.wild_pitches', 'home_team.pitching_stats.pickoffs', 'home_team.pitching_stats.ground_outs_to_airouts', 'home_team.pitching_stats.win_percentage', 'home_team.pitching_stats.pitches_per_inning', 'home_team.pitching_stats.games_finished', 'home_team.pitching_stats.strikeout_walk_ratio', 'home_team.pitching_stats.strikeouts_per_9inn', 'home_team.pitching_stats.walks_per_9inn', 'home_team.pitching_stats.hits_per_9inn', 'home_team.pitching_stats.runs_scored_per_9', 'home_team.pitching_stats.home_runs_per_9', 'home_team.pitching_stats.sac_bunts', 'home_team.pitching_stats.sac_flies', 'home_team.fielding_stats.games_played', 'home_team.fielding_stats.games_started', 'home_team.fielding_stats.assists', 'home_team.fielding_stats.put_outs', 'home_team.fielding_stats.errors', 'home_team.fielding_stats.chances', 'home_team.fielding_stats.fielding', 'home_team.fielding_stats.range_factor_per_game', 'home_team.fielding_stats.range_factor_per_9inn', 'home_team.fielding_stats.innings', 'home_team.fielding_stats.games', 'home_team.fielding_stats.double_plays', 'home_team.fielding_stats.triple_plays', 'home_team.fielding_stats.throwing_errors', 'away_team.team_id', 'away_team.batting_stats.games_played', 'away_team.batting_stats.ground_outs', 'away_team.batting_stats.air_outs', 'away_team.batting_stats.runs', 'away_team.batting_stats.doubles', 'away_team.batting_stats.triples', 'away_team.batting_stats.home_runs', 'away_team.batting_stats.strike_outs', 'away_team.batting_stats.base_on_balls', 'away_team.batting_stats.intentional_walks', 'away_team.batting_stats.hits', 'away_team.batting_stats.hit_by_pitch', 'away_team.batting_stats.avg', 'away_team.batting_stats.at_bats', 'away_team.batting_stats.obp', 'away_team.batting_stats.slg', 'away_team.batting_stats.ops', 'away_team.batting_stats.caught_stealing', 'away_team.batting_stats.stolen_bases', 'away_team.batting_stats.stolen_base_percentage', 'away_team.batting_stats.ground_into_double_play', 'away_team.batting_stats.number_of_pitches', 'away_team.batting_stats.plate_appearances', 'away_team.batting_stats.total_bases', 'away_team.batting_stats.rbi', 'away_team.batting_stats.left_on_base', 'away_team.batting_stats.sac_bunts', 'away_team.batting_stats.sac_flies', 'away_team.batting_stats.babip', 'away_team.batting_stats.ground_outs_to_airouts', 'away_team.batting_stats.at_bats_per_home_run', 'away_team.pitching_stats.games_played', 'away_team.pitching_stats.games_started', 'away_team.pitching_stats.ground_outs', 'away_team.pitching_stats.air_outs', 'away_team.pitching_stats.runs', 'away_team.pitching_stats.doubles', 'away_team.pitching_stats.triples', 'away_team.pitching_stats.home_runs', 'away_team.pitching_stats.strike_outs', 'away_team.pitching_stats.base_on_balls', 'away_team.pitching_stats.intentional_walks', 'away_team.pitching_stats.hits', 'away_team.pitching_stats.hit_by_pitch', 'away_team.pitching_stats.avg', 'away_team.pitching_stats.at_bats', 'away_team.pitching_stats.era', 'away_team.pitching_stats.innings_pitched', 'away_team.pitching_stats.wins', 'away_team.pitching_stats.losses', 'away_team.pitching_stats.ties', 'away_team.pitching_stats.saves', 'away_team.pitching_stats.save_opportunities', 'away_team.pitching_stats.holds', 'away_team.pitching_stats.blown_saves', 'away_team.pitching_stats.earned_runs', 'away_team.pitching_stats.whip', 'away_team.pitching_stats.batters_faced', 'away_team.pitching_stats.outs', 'away_team.pitching_stats.games_pitched', 'away_team.pitching_stats.complete_games', 'away_team.pitching_stats.shutouts', 'away_team.pitching_stats.strikes', 'away_team.pitching_stats.strike_percentage', 'away_team.pitching_stats.hit_batsmen', 'away_team.pitching_stats.balks', 'away_team.pitching_stats.wild_pitches', 'away_team.pitching_stats.pickoffs', 'away_team.pitching_stats.ground_outs_to_airouts', 'away_team.pitching_stats.win_percentage', 'away_team.pitching_stats.pitches_per_inning', 'away_team.pitching_stats.games_finished', 'away_team.pitching_stats.strikeout_walk_ratio', 'away_team.pitching_stats.strikeouts_per_9inn', 'away_team.pitching_stats.walks_per_9inn', 'away_team.pitching_stats.hits_per_9inn', 'away_team.pitching_stats.runs_scored_per_9', 'away_team.pitching_stats.home_runs_per_9', 'away_team.pitching_stats.sac_bunts', 'away_team.pitching_stats.sac_flies', 'away_team.fielding_stats.games_played', 'away_team.fielding_stats.games_started', 'away_team.fielding_stats.assists', 'away_team.fielding_stats.put_outs', 'away_team.fielding_stats.errors', 'away_team.fielding_stats.chances', 'away_team.fielding_stats.fielding', 'away_team.fielding_stats.range_factor_per_game', 'away_team.fielding_stats.range_factor_per_9inn', 'away_team.fielding_stats.innings', 'away_team.fielding_stats.games', 'away_team.fielding_stats.double_plays', 'away_team.fielding_stats.triple_plays', 'away_team.fielding_stats.throwing_errors', 'home_team.starting_pitcher.hand', 'home_team.starting_pitcher.recent.games', 'home_team.starting_pitcher.recent.games_started', 'home_team.starting_pitcher.recent.outs', 'home_team.starting_pitcher.recent.batters_faced', 'home_team.starting_pitcher.recent.number_of_pitches', 'home_team.starting_pitcher.recent.hits', 'home_team.starting_pitcher.recent.home_runs', 'home_team.starting_pitcher.recent.base_on_balls', 'home_team.starting_pitcher.recent.strike_outs', 'home_team.starting_pitcher.recent.runs', 'home_team.starting_pitcher.recent.earned_runs', 'home_team.starting_pitcher.recent.era', 'home_team.starting_pitcher.recent.whip', 'home_team.starting_pitcher.recent.strikeouts_per_9inn', 'home_team.starting_pitcher.recent.walks_per_9inn', 'home_team.starting_pitcher.season.games', 'home_team.starting_pitcher.season.games_started', 'home_team.starting_pitcher.season.outs', 'home_team.starting_pitcher.season.batters_faced', 'home_team.starting_pitcher.season.number_of_pitches', 'home_team.starting_pitcher.season.hits', 'home_team.starting_pitcher.season.home_runs', 'home_team.starting_pitcher.season.base_on_balls', 'home_team.starting_pitcher.season.strike_outs', 'home_team.starting_pitcher.season.runs', 'home_team.starting_pitcher.season.earned_runs', 'home_team.starting_pitcher.season.era', 'home_team.starting_pitcher.season.whip', 'home_team.starting_pitcher.season.strikeouts_per_9inn', 'home_team.starting_pitcher.season.walks_per_9inn', 'away_team.starting_pitcher.hand', 'away_team.starting_pitcher.recent.games', 'away_team.starting_pitcher.recent.games_started', 'away_team.starting_pitcher.recent.outs', 'away_team.starting_pitcher.recent.batters_faced', 'away_team.starting_pitcher.recent.number_of_pitches', 'away_team.starting_pitcher.recent.hits', 'away_team.starting_pitcher.recent.home_runs', 'away_team.starting_pitcher.recent.base_on_balls', 'away_team.starting_pitcher.recent.strike_outs', 'away_team.starting_pitcher.recent.runs', 'away_team.starting_pitcher.recent.earned_runs', 'away_team.starting_pitcher.recent.era', 'away_team.starting_pitcher.recent.whip', 'away_team.starting_pitcher.recent.strikeouts_per_9inn', 'away_team.starting_pitcher.recent.walks_per_9inn', 'away_team.starting_pitcher.season.games', 'away_team.starting_pitcher.season.games_started', 'away_team.starting_pitcher.season.outs', 'away_team.starting_pitcher.season.batters_faced', 'away_team.starting_pitcher.season.number_of_pitches', 'away_team.starting_pitcher.season.hits', 'away_team.starting_pitcher.season.home_runs', 'away_team.starting_pitcher.season.base_on_balls', 'away_team.starting_pitcher.season.strike_outs', 'away_team.starting_pitcher.season.runs', 'away_team.starting_pitcher.season.earned_runs', 'away_team.starting_pitcher.season.era', 'away_team.starting_pitcher.season.whip', 'away_team.starting_pitcher.season.strikeouts_per_9inn', 'away_team.starting_pitcher.season.walks_per_9inn', 'home_team.bullpen.stats.games', 'home_team.bullpen.stats.outs', 'home_team.bullpen.stats.batters_faced', 'home_team.bullpen.stats.number_of_pitches', 'home_team.bullpen.stats.hits', 'home_team.bullpen.stats.home_runs', 'home_team.bullpen.stats.base_on_balls', 'home_team.bullpen.stats.strike_outs', 'home_team.bullpen.stats.runs', 'home_team.bullpen.stats.earned_runs', 'home_team.bullpen.stats.era', 'home_team.bullpen.stats.whip', 'home_team.bullpen.stats.strikeouts_per_9inn', 'home_team.bullpen.stats.walks_per_9inn', 'home_team.bullpen.last_1_day.appearances', 'home_team.bullpen.last_1_day.number_of_pitches', 'home_team.bullpen.last_3_days.appearances', 'home_team.bullpen.last_3_days.number_of_pitches', 'home_team.bullpen.last_7_days.appearances', 'home_team.bullpen.last_7_days.number_of_pitches', 'away_team.bullpen.stats.games', 'away_team.bullpen.stats.outs', 'away_team.bullpen.stats.batters_faced', 'away_team.bullpen.stats.number_of_pitches', 'away_team.bullpen.stats.hits', 'away_team.bullpen.stats.home_runs', 'away_team.bullpen.stats.base_on_balls', 'away_team.bullpen.stats.strike_outs', 'away_team.bullpen.stats.runs', 'away_team.bullpen.stats.earned_runs', 'away_team.bullpen.stats.era', 'away_team.bullpen.stats.whip', 'away_team.bullpen.stats.strikeouts_per_9inn', 'away_team.bullpen.stats.walks_per_9inn', 'away_team.bullpen.last_1_day.appearances', 'away_team.bullpen.last_1_day.number_of_pitches', 'away_team.bullpen.last_3_days.appearances', 'away_team.bullpen.last_3_days.number_of_pitches', 'away_team.bullpen.last_7_days.appearances', 'away_team.bullpen.last_7_days.number_of_pitches', 'home_team.rest.days_since_last_game', 'home_team.rest.games_last_7_days', 'home_team.rest.games_last_14_days', 'home_team.rest.consecutive_road_games', 'home_team.rest.timezone_shift', 'home_team.rest.travel_distance', 'away_team.rest.days_since_last_game', 'away_team.rest.games_last_7_days', 'away_team.rest.games_last_14_days', 'away_team.rest.consecutive_road_games', 'away_team.rest.timezone_shift', 'away_team.rest.travel_distance', 'home_team.splits.home_hitting.games_played', 'home_team.splits.home_hitting.runs', 'home_team.splits.home_hitting.plate_appearances', 'home_team.splits.home_hitting.home_runs', 'home_team.splits.home_hitting.strike_outs', 'home_team.splits.home_hitting.base_on_balls', 'home_team.splits.home_hitting.avg', 'home_team.splits.home_hitting.obp', 'home_team.splits.home_hitting.slg', 'home_team.splits.home_hitting.ops', 'home_team.splits.road_hitting.games_played', 'home_team.splits.road_hitting.runs', 'home_team.splits.road_hitting.plate_appearances', 'home_team.splits.road_hitting.home_runs', 'home_team.splits.road_hitting.strike_outs', 'home_team.splits.road_hitting.base_on_balls', 'home_team.splits.road_hitting.avg', 'home_team.splits.road_hitting.obp', 'home_team.splits.road_hitting.slg', 'home_team.splits.road_hitting.ops', 'home_team.splits.vs_left_hitting.games_played', 'home_team.splits.vs_left_hitting.runs', 'home_team.splits.vs_left_hitting.plate_appearances', 'home_team.splits.vs_left_hitting.home_runs', 'home_team.splits.vs_left_hitting.strike_outs', 'home_team.splits.vs_left_hitting.base_on_balls', 'home_team.splits.vs_left_hitting.avg', 'home_team.splits.vs_left_hitting.obp', 'home_team.splits.vs_left_hitting.slg', 'home_team.splits.vs_left_hitting.ops', 'home_team.splits.vs_right_hitting.games_played', 'home_team.splits.vs_right_hitting.runs', 'home_team.splits.vs_right_hitting.plate_appearances', 'home_team.splits.vs_right_hitting.home_runs', 'home_team.splits.vs_right_hitting.strike_outs', 'home_team.splits.vs_right_hitting.base_on_balls', 'home_team.splits.vs_right_hitting.avg', 'home_team.splits.vs_right_hitting.obp', 'home_team.splits.vs_right_hitting.slg', 'home_team.splits.vs_right_hitting.ops', 'home_team.splits.home_pitching.games_played', 'home_team.splits.home_pitching.runs', 'home_team.splits.home_pitching.era', 'home_team.splits.home_pitching.whip', 'home_team.splits.home_pitching.strikeouts_per_9inn', 'home_team.splits.home_pitching.walks_per_9inn', 'home_team.splits.home_pitching.home_runs_per_9', 'home_team.splits.road_pitching.games_played', 'home_team.splits.road_pitching.runs', 'home_team.splits.road_pitching.era', 'home_team.splits.road_pitching.whip', 'home_team.splits.road_pitching.strikeouts_per_9inn', 'home_team.splits.road_pitching.walks_per_9inn', 'home_team.splits.road_pitching.home_runs_per_9', 'away_team.splits.home_hitting.games_played', 'away_team.splits.home_hitting.runs', 'away_team.splits.home_hitting.plate_appearances', 'away_team.splits.home_hitting.home_runs', 'away_team.splits.home_hitting.strike_outs', 'away_team.splits.home_hitting.base_on_balls', 'away_team.splits.home_hitting.avg', 'away_team.splits.home_hitting.obp', 'away_team.splits.home_hitting.slg', 'away_team.splits.home_hitting.ops', 'away_team.splits.road_hitting.games_played', 'away_team.splits.road_hitting.runs', 'away_team.splits.road_hitting.plate_appearances', 'away_team.splits.road_hitting.home_runs', 'away_team.splits.road_hitting.strike_outs', 'away_team.splits.road_hitting.base_on_balls', 'away_team.splits.road_hitting.avg', 'away_team.splits.road_hitting.obp', 'away_team.splits.road_hitting.slg', 'away_team.splits.road_hitting.ops', 'away_team.splits.vs_left_hitting.games_played', 'away_team.splits.vs_left_hitting.runs', 'away_team.splits.vs_left_hitting.plate_appearances', 'away_team.splits.vs_left_hitting.home_runs', 'away_team.splits.vs_left_hitting.strike_outs', 'away_team.splits.vs_left_hitting.base_on_balls', 'away_team.splits.vs_left_hitting.avg', 'away_team.splits.vs_left_hitting.obp', 'away_team.splits.vs_left_hitting.slg', 'away_team.splits.vs_left_hitting.ops', 'away_team.splits.vs_right_hitting.games_played', 'away_team.splits.vs_right_hitting.runs', 'away_team.splits.vs_right_hitting.plate_appearances', 'away_team.splits.vs_right_hitting.home_runs', 'away_team.splits.vs_right_hitting.strike_outs', 'away_team.splits.vs_right_hitting.base_on_balls', 'away_team.splits.vs_right_hitting.avg', 'away_team.splits.vs_right_hitting.obp', 'away_team.splits.vs_right_hitting.slg', 'away_team.splits.vs_right_hitting.ops', 'away_team.splits.home_pitching.games_played', 'away_team.splits.home_pitching.runs', 'away_team.splits.home_pitching.era', 'away_team.splits.home_pitching.whip', 'away_team.splits.home_pitching.strikeouts_per_9inn', 'away_team.splits.home_pitching.walks_per_9inn', 'away_team.splits.home_pitching.home_runs_per_9', 'away_team.splits.road_pitching.games_played', 'away_team.splits.road_pitching.runs', 'away_team.splits.road_pitching.era', 'away_team.splits.road_pitching.whip', 'away_team.splits.road_pitching.strikeouts_per_9inn', 'away_team.splits.road_pitching.walks_per_9inn', 'away_team.splits.road_pitching.home_runs_per_9', 'setting.scheduled_series.game_in_series', 'setting.scheduled_series.games_in_series', 'head_to_head.season.games', 'head_to_head.season.home_team_wins', 'head_to_head.season.away_team_wins', 'head_to_head.season.run_differential', 'head_to_head.season.average_total_runs', 'head_to_head.recent.games', 'head_to_head.recent.home_team_wins', 'head_to_head.recent.away_team_wins', 'head_to_head.recent.run_differential', 'head_to_head.recent.average_total_runs', 'home_team.standing.division_id', 'home_team.standing.wins', 'home_team.standing.losses', 'home_team.standing.win_percentage', 'home_team.standing.games_back', 'home_team.standing.wild_card_games_back', 'home_team.standing.division_rank', 'home_team.standing.run_differential', 'home_team.standing.streak', 'home_team.standing.last_10_wins', 'home_team.standing.last_10_losses', 'away_team.standing.division_id', 'away_team.standing.wins', 'away_team.standing.losses', 'away_team.standing.win_percentage', 'away_team.standing.games_back', 'away_team.standing.wild_card_games_back', 'away_team.standing.division_rank', 'away_team.standing.run_differential', 'away_team.standing.streak', 'away_team.standing.last_10_wins', 'away_team.standing.last_10_losses', 'home_team.ratings.elo', 'home_team.ratings.offense', 'home_team.ratings.defense', 'home_team.ratings.expected_runs', 'home_team.ratings.expected_runs_uncertainty', 'home_team.ratings.massey_offense', 'home_team.ratings.massey_defense', 'home_team.ratings.bradley_terry', 'home_team.expected_wins.pythagenpat_exponent', 'home_team.expected_wins.pythagorean_win_percentage', 'home_team.expected_wins.base_runs_per_game', 'home_team.expected_wins.base_runs_allowed_per_game', 'home_team.expected_wins.base_runs_win_percentage', 'away_team.ratings.elo', 'away_team.ratings.offense', 'away_team.ratings.defense', 'away_team.ratings.expected_runs', 'away_team.ratings.expected_runs_uncertainty', 'away_team.ratings.massey_offense', 'away_team.ratings.massey_defense', 'away_team.ratings.bradley_terry', 'away_team.expected_wins.pythagenpat_exponent', 'away_team.expected_wins.pythagorean_win_percentage', 'away_team.expected_wins.base_runs_per_game', 'away_team.expected_wins.base_runs_allowed_per_game', 'away_team.expected_wins.base_runs_win_percentage', 'result.home_team_score', 'result.away_team_score', 'result.linescore.first_five.home', 'result.linescore.first_five.away', 'result.linescore.regulation.home', 'result.linescore.regulation.away', 'result.linescore.innings_played', 'result.linescore.extra_innings', 'result.linescore.home.hits', 'result.linescore.home.errors', 'result.linescore.home.left_on_base', 'result.linescore.away.hits', 'result.linescore.away.errors', 'result.linescore.away.left_on_base']
 total_data = {key: [] for key in FEATURES}
 
+# rate stats that are undefined for a game's sample (e.g. stolen base percentage with no attempts) are stored as null,
+# and are written as empty cells, which pandas reads as NaN
+MISSING = ""
+
+# categorical values are written as integer codes, by the last part of their feature key. values without a code (e.g. a
+# weather condition the api added later) are written as missing
+CATEGORIES = {
+    "hand": {"left": 0, "right": 1, "switch": 2},
+    "roof_type": {"open": 0, "retractable": 1, "dome": 2},
+    "surface": {"grass": 0, "artificial_turf": 1},
+    "day_night": {"day": 0, "night": 1},
+    "condition": {
+        "clear": 0,
+        "sunny": 1,
+        "partly_cloudy": 2,
+        "cloudy": 3,
+        "overcast": 4,
+        "drizzle": 5,
+        "rain": 6,
+        "snow": 7,
+        "roof_closed": 8,
+        "dome": 9,
+    },
+    "wind_direction": {
+        "calm": 0,
+        "varies": 1,
+        "out_to_left": 2,
+        "out_to_center": 3,
+        "out_to_right": 4,
+        "in_from_left": 5,
+        "in_from_center": 6,
+        "in_from_right": 7,
+        "left_to_right": 8,
+        "right_to_left": 9,
+    },
+    "double_header": {"no": 0, "traditional": 1, "split": 2},
+}
+
 
 def get_value_from_feature_key(data: dict, feature_key: str) -> str:
     keys = feature_key.split(".")
     value = data
     for key in keys:
+        # features added after a game was saved (or absent for it, like an unannounced starter) are missing
+        if value is None or key not in value:
+            return MISSING
         value = value[key]
-    return value
+    if value is None:
+        return MISSING
+    return CATEGORIES[keys[-1]].get(value, MISSING) if isinstance(value, str) else value
 
 
 for year in os.listdir("data"):
-    if year == "data.csv" or year == "progress.json":
+    # skip everything that isn't a season directory (data.csv, progress.json, boxscores/, ...)
+    if not year.isdigit():
         continue
 
     for home_team_id in tqdm(os.listdir(f"data/{year}")):
         for date in os.listdir(f"data/{year}/{home_team_id}"):
             with open(f"data/{year}/{home_team_id}/{date}", "r") as f:
                 data = json.load(f)
+            # games saved before stat windows ended the day before the game have team stats that include the game itself;
+            # `cargo run refetch <season>` brings them in line
+            if data["setting"].get("stats_through") is None:
+                continue
 
             for feature in FEATURES:
                 total_data[feature].append(get_value_from_feature_key(data, feature))
@@ -0,0 +1,33 @@
+import json
+import math
+import os
+
+
+# models, the scalers they were trained with and fixtures of their predictions, for the rust crate to load
+MODELS_DIR = "data/models"
+
+# the number of test rows saved in each fixture
+FIXTURE_ROWS = 200
+
+
+def save_scaler(scaler, feature_names, name: str, directory: str = MODELS_DIR) -> None:
+    os.makedirs(directory, exist_ok=True)
+    with open(f"{directory}/{name}_scaler.json", "w") as f:
+        json.dump({
+            "feature_names": list(feature_names),
+            "means": scaler.mean_.tolist(),
+            "scales": scaler.scale_.tolist(),
+        }, f)
+
+
+def save_fixture(features, predictions, name: str, directory: str = MODELS_DIR) -> None:
+    """Save unscaled feature rows and the model's predictions for them, which the rust crate must reproduce."""
+    os.makedirs(directory, exist_ok=True)
+    # json has no NaN, so missing values are null
+    rows = [[None if math.isnan(value) else float(value) for value in row] for row in features.to_numpy()[:FIXTURE_ROWS]]
+    with open(f"{directory}/{name}_fixture.json", "w") as f:
+        json.dump({
+            "feature_names": list(features.columns),
+            "rows": rows,
+            "predictions": [[float(value) for value in prediction] for prediction in predictions[:FIXTURE_ROWS]],
+        }, f)
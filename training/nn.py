@@ -6,22 +6,34 @@ from tensorflow.keras.layers import Dense, Dropout
 from tensorflow.keras.optimizers import Adam
 from tensorflow.keras.callbacks import EarlyStopping
 
+import numpy as np
 import pandas as pd
+import tensorflow as tf
+import tf2onnx
+
+from export import MODELS_DIR, FIXTURE_ROWS, save_fixture, save_scaler
 
 
 data = pd.read_csv("data/data.csv")
 
 targets = data[["result.home_team_score", "result.away_team_score"]]
-features = data.drop(columns=["result.home_team_score", "result.away_team_score"])
+# every result.* column is an outcome of the game, not a feature of it
+features = data.drop(columns=[column for column in data.columns if column.startswith("result.")])
 
 X_train, X_test, y_train, y_test = train_test_split(features, targets, test_size=0.2, random_state=42)
 print(f"{X_train.shape=}")
 print(f"{y_train.shape=}")
 
+X_test_unscaled = X_test
+
 scaler = StandardScaler()
 X_train = scaler.fit_transform(X_train)
 X_test = scaler.transform(X_test)
 
+# undefined rate stats are NaN; after scaling, zero is the training mean
+X_train = np.nan_to_num(X_train)
+X_test = np.nan_to_num(X_test)
+
 model = Sequential([
     Dense(128, activation="relu", input_shape=(X_train.shape[1],)),
     Dropout(0.2),
@@ -41,4 +53,9 @@ history = model.fit(
     epochs=200,
     batch_size=32,
     callbacks=[EarlyStopping(patience=10)],
-)
\ No newline at end of file
+)
+
+save_scaler(scaler, features.columns, "nn")
+input_signature = (tf.TensorSpec((None, X_train.shape[1]), tf.float32, name="features"),)
+tf2onnx.convert.from_keras(model, input_signature=input_signature, output_path=f"{MODELS_DIR}/nn.onnx")
+save_fixture(X_test_unscaled, model.predict(X_test[:FIXTURE_ROWS]), "nn")
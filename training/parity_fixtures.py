@@ -0,0 +1,86 @@
+"""
+Train small models the way `train.py` and `nn.py` do and export them with `export.py` into `tests/fixtures`, for
+`tests/parity.rs` to check the rust predictions against.
+
+The data is synthetic (3 features with missing values, 2 targets) so the models stay small enough to commit. The
+expected predictions come from xgboost's `Booster.predict` and from onnxruntime running the exported ONNX file, not
+from anything the rust crate implements. Run this from the repository root after changing how models are trained or
+exported, and commit the files it writes.
+"""
+
+import numpy as np
+import onnxruntime
+import pandas as pd
+import tensorflow as tf
+import tf2onnx
+import xgboost as xgb
+from sklearn.model_selection import train_test_split
+from sklearn.preprocessing import StandardScaler
+from tensorflow.keras.layers import Dense, Dropout
+from tensorflow.keras.models import Sequential
+
+from export import FIXTURE_ROWS, save_fixture, save_scaler
+
+
+FIXTURES_DIR = "tests/fixtures"
+
+ROWS = 400
+# the share of feature values left missing
+MISSING = 0.1
+
+
+rng = np.random.default_rng(42)
+features = pd.DataFrame(rng.normal([1.0, 2.0, 3.0], [2.0, 1.0, 0.5], size=(ROWS, 3)), columns=["a", "b", "c"])
+targets = pd.DataFrame({
+    "home": 4.5 + 0.8 * features["a"] - 0.5 * features["c"] + rng.normal(0, 1, ROWS),
+    "away": 4.2 - 0.3 * features["a"] + 0.6 * features["b"] + rng.normal(0, 1, ROWS),
+})
+features = features.mask(rng.random(features.shape) < MISSING)
+
+X_train, X_test, y_train, y_test = train_test_split(features, targets, test_size=0.2, random_state=42)
+X_test_unscaled = X_test
+
+scaler = StandardScaler()
+X_train = scaler.fit_transform(X_train)
+X_test = scaler.transform(X_test)
+
+
+# xgboost, as in train.py but with fewer rounds, so early stopping leaves trees past the best iteration in the model
+
+dtrain = xgb.DMatrix(X_train, y_train)
+dtest = xgb.DMatrix(X_test, y_test)
+model = xgb.train(
+    {"learning_rate": 0.1, "max_depth": 2, "subsample": 0.6, "colsample_bytree": 0.7, "min_child_weight": 6},
+    dtrain,
+    num_boost_round=60,
+    early_stopping_rounds=5,
+    evals=[(dtrain, "train"), (dtest, "test")],
+    verbose_eval=False,
+)
+
+save_scaler(scaler, features.columns, "xgboost", FIXTURES_DIR)
+model.save_model(f"{FIXTURES_DIR}/xgboost.json")
+predictions = model.predict(xgb.DMatrix(X_test[:FIXTURE_ROWS]), iteration_range=(0, model.best_iteration + 1))
+save_fixture(X_test_unscaled, predictions, "xgboost", FIXTURES_DIR)
+
+
+# a neural network, as in nn.py but smaller, exported with tf2onnx and run with onnxruntime
+
+X_train = np.nan_to_num(X_train)
+X_test = np.nan_to_num(X_test)
+
+tf.keras.utils.set_random_seed(42)
+nn = Sequential([
+    Dense(8, activation="relu", input_shape=(X_train.shape[1],)),
+    Dropout(0.2),
+    Dense(2, activation="linear"),
+])
+nn.compile(optimizer="adam", loss="mse")
+nn.fit(X_train, y_train, epochs=20, batch_size=32, verbose=0)
+
+save_scaler(scaler, features.columns, "nn", FIXTURES_DIR)
+input_signature = (tf.TensorSpec((None, X_train.shape[1]), tf.float32, name="features"),)
+tf2onnx.convert.from_keras(nn, input_signature=input_signature, output_path=f"{FIXTURES_DIR}/nn.onnx")
+session = onnxruntime.InferenceSession(f"{FIXTURES_DIR}/nn.onnx")
+(predictions,) = session.run(None, {"features": X_test[:FIXTURE_ROWS].astype(np.float32)})
+save_fixture(X_test_unscaled, predictions, "nn", FIXTURES_DIR)
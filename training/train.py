@@ -6,16 +6,21 @@ from sklearn.metrics import r2_score, mean_absolute_error
 import pandas as pd
 import xgboost as xgb
 
+from export import MODELS_DIR, FIXTURE_ROWS, save_fixture, save_scaler
+
 
 data = pd.read_csv("data/data.csv")
 
 targets = data[["result.home_team_score", "result.away_team_score"]]
-features = data.drop(columns=["result.home_team_score", "result.away_team_score"])
+# every result.* column is an outcome of the game, not a feature of it
+features = data.drop(columns=[column for column in data.columns if column.startswith("result.")])
 
 X_train, X_test, y_train, y_test = train_test_split(features, targets, test_size=0.2, random_state=42)
 print(y_train.describe())
 print()
 
+X_test_unscaled = X_test
+
 scaler = StandardScaler()
 X_train = scaler.fit_transform(X_train)
 X_test = scaler.transform(X_test)
@@ -92,4 +97,8 @@ mae = mean_absolute_error(y_test, y_pred)
 print()
 print(f"best_score: {best_score}")
 print(f"r2: {r2}")
-print(f"mae: {mae}")
\ No newline at end of file
+print(f"mae: {mae}")
+
+save_scaler(scaler, features.columns, "xgboost")
+model.save_model(f"{MODELS_DIR}/xgboost.json")
+save_fixture(X_test_unscaled, model.predict(xgb.DMatrix(X_test[:FIXTURE_ROWS]), iteration_range=(0, model.best_iteration + 1)), "xgboost")